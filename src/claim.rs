@@ -29,40 +29,357 @@ pub struct JsonPairOrderRewards {
 pub struct AccountRewardsData {
     pub account_address: String,
     #[mutable]
-    pub rewards: HashMap<String, HashMap<ResourceAddress, Decimal>> // HashMap<Reward Name, HashMap<Token Address, Token Reward>>
+    pub rewards: HashMap<String, HashMap<ResourceAddress, Decimal>>, // HashMap<Reward Name, HashMap<Token Address, Token Reward>>
+}
+
+#[derive(ScryptoSbor, Clone, Debug)]
+pub struct PayoutSplit {
+    pub account_address: ComponentAddress,
+    pub percentage: Decimal, // percentage of the claimed rewards, all splits of an NFT add up to 100
+}
+
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug)]
+pub enum ClaimDelegate {
+    Badge(ResourceOrNonFungible), // a holder of this badge can claim
+    Account(ComponentAddress), // the owner of this account can claim
+}
+
+#[derive(ScryptoSbor, Clone, Debug)]
+pub struct DelegateGrant {
+    pub delegate: ClaimDelegate,
+    pub expires_at_epoch: u64, // last epoch in which the delegate can claim
 }
 
 #[derive(ScryptoSbor, Clone, Debug, NonFungibleData)]
 pub struct OrderRewardsData {
     pub order_id: String,
     #[mutable]
-    pub rewards: HashMap<String, HashMap<ResourceAddress, Decimal>> // HashMap<Reward Name, HashMap<Token Address, Token Reward>>
+    pub rewards: HashMap<String, HashMap<ResourceAddress, Decimal>>, // HashMap<Reward Name, HashMap<Token Address, Token Reward>>
+}
+
+// Data that is added to account NFTs over time. The NFT data schema is fixed when the NFT resource is created,
+// so this data is kept by the component, keyed by NFT id. Records keep the version they were written with
+// and are upgraded to the latest version when they are written again, or by a super_admin.
+#[derive(ScryptoSbor, Clone, Debug)]
+pub enum VersionedAccountData {
+    V1(AccountDataV1),
+    V2(AccountDataV2),
+}
+
+impl VersionedAccountData {
+    pub fn upgrade(self) -> AccountDataV2 {
+        match self {
+            VersionedAccountData::V1(data) => AccountDataV2 {
+                total_claimed: data.total_claimed,
+                last_claim_epoch: data.last_claim_epoch,
+                payout_splits: vec![],
+            },
+            VersionedAccountData::V2(data) => data,
+        }
+    }
+}
+
+#[derive(ScryptoSbor, Clone, Debug, Default)]
+pub struct AccountDataV1 {
+    pub total_claimed: HashMap<ResourceAddress, Decimal>,
+    pub last_claim_epoch: Option<u64>,
+}
+
+#[derive(ScryptoSbor, Clone, Debug, Default)]
+pub struct AccountDataV2 {
+    pub total_claimed: HashMap<ResourceAddress, Decimal>,
+    pub last_claim_epoch: Option<u64>,
+    pub payout_splits: Vec<PayoutSplit>, // if set, claimed rewards are deposited to these accounts instead of returned to the claimer
+}
+
+// Account state that is moved to a successor component by migrate_accounts.
+#[derive(ScryptoSbor, Clone, Debug)]
+pub struct MigratedAccountData {
+    pub account_address: ComponentAddress,
+    pub pending_rewards: Option<HashMap<String, HashMap<ResourceAddress, Decimal>>>,
+    pub account_data: Option<VersionedAccountData>,
+    pub nft_rewards: Option<HashMap<String, HashMap<ResourceAddress, Vec<NonFungibleLocalId>>>>,
+    pub claim_delegate: Option<DelegateGrant>,
+    pub frozen_at_epoch: Option<u64>,
+    pub faucet_claims: Vec<u64>, // ids of the migrated faucets the account has claimed from, as numbered by the successor
+}
+
+#[derive(ScryptoSbor, NonFungibleData, Clone, Debug)]
+pub struct CampaignManagerData {
+    pub name: String,
+    pub campaign_id: String,
+}
+
+#[derive(ScryptoSbor, Clone, Debug)]
+pub struct CampaignData {
+    pub campaign_id: String, // rewards added through the campaign use the reward name "Campaign: " + campaign id
+    pub reward_token: ResourceAddress,
+    pub budget: Decimal,
+    pub allocated: Decimal, // rewards currently allocated through the campaign, never more than budget
+}
+
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub enum RaffleTicket {
+    Account(ComponentAddress),
+    Order(NonFungibleGlobalId), // order receipt of the order
+}
+
+#[derive(ScryptoSbor, Clone, Debug)]
+pub struct RaffleData {
+    pub reward_name: String,
+    pub reward_token: ResourceAddress,
+    pub prizes: Vec<Decimal>, // prize amounts, drawn in this order
+    pub seed_hash: Hash, // hash of the secret seed, committed when the raffle is created and verified when it is drawn
+    pub entry_deadline_epoch: u64, // last epoch in which tickets can be added, the raffle can be closed after this epoch
+    pub reveal_deadline_epoch: u64, // last epoch in which the raffle can be drawn, after this epoch the raffle can be cancelled
+    pub tickets: Vec<(RaffleTicket, u64)>, // tickets with their weights
+    pub entropy: Option<Hash>, // set when the raffle is closed, from the seed hash, the final tickets and the entry deadline. Mixed with the seed when drawing
+    pub drawn: bool,
+    pub cancelled: bool,
+}
+
+#[derive(ScryptoSbor, Clone, Debug)]
+pub struct FaucetData {
+    pub reward_token: ResourceAddress,
+    pub amount_per_claim: Decimal,
+    pub remaining: Decimal, // tokens left in the faucet, held in the claim vault
+    pub access_rule: AccessRule, // rule an account must pass to claim from the faucet
+}
+
+#[derive(ScryptoSbor, Clone, Debug)]
+pub struct AccrualConfig {
+    pub pair_component: ComponentAddress, // only this component can record fills for the orders of the order receipt
+    pub reward_name: String,
+    pub reward_token: ResourceAddress,
+    pub rate_table: Vec<(Decimal, Decimal)>, // (minimum fill volume, reward per unit of volume), sorted by minimum fill volume
+    pub remaining_budget: Decimal, // rewards that can still be accrued, held in the claim vault
+}
+
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub enum RewardEntry {
+    Account(ComponentAddress),
+    Order(String), // order id, same as the keys of order_rewards
+}
+
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub enum RewardEntryStatus {
+    Applied,
+    Clamped, // removal was limited to the existing reward
+    DepositRefusedPending, // the account refused the rewards NFT, so the rewards were stored as pending rewards
+    SkippedNotFound, // no NFT, pending rewards or order found to remove rewards from
+}
+
+#[derive(ScryptoSbor, Clone, Debug)]
+pub struct RewardEntryReport {
+    pub entry: RewardEntry,
+    pub requested_amount: Decimal,
+    pub applied_amount: Decimal,
+    pub status: RewardEntryStatus,
+}
+
+#[derive(ScryptoSbor, Clone, Debug)]
+pub struct RewardsReport {
+    pub reward_name: String,
+    pub reward_token: ResourceAddress,
+    pub total_amount: Decimal,
+    pub entries: Vec<RewardEntryReport>,
+}
+
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct ClaimCursor {
+    pub order_receipt_address: ResourceAddress,
+    pub order_id: NonFungibleLocalId, // last order processed in the previous claim
+}
+
+#[derive(ScryptoSbor, Clone, Debug)]
+pub struct ClaimFee {
+    pub basis_points: u16, // fee as a fraction of the claimed amount, 1 basis point = 0.01%
+    pub minimum: Decimal, // flat minimum fee, limited to the claimed amount
+}
+
+#[derive(ScryptoSbor, Clone, Debug)]
+pub struct ClaimedTokenAmount {
+    pub token_address: ResourceAddress,
+    pub gross_amount: Decimal,
+    pub fee_amount: Decimal,
+    pub net_amount: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug)]
+pub struct RewardsClaimedEvent {
+    pub claimed_amounts: Vec<ClaimedTokenAmount>,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug)]
+pub struct RewardsFrozenEvent {
+    pub account_ids: Vec<NonFungibleLocalId>,
+    pub order_ids: Vec<String>,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug)]
+pub struct RewardsUnfrozenEvent {
+    pub account_ids: Vec<NonFungibleLocalId>,
+    pub order_ids: Vec<String>,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug)]
+pub struct PendingRewardsEvent {
+    pub account_address: ComponentAddress,
+    pub reward_name: String,
+    pub reward_token: ResourceAddress,
+    pub amount: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug)]
+pub struct RaffleDrawnEvent {
+    pub raffle_id: u64,
+    pub seed: String,
+    pub entropy: Hash,
+    pub winners: Vec<(RaffleTicket, Decimal)>,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug)]
+pub struct RaffleCancelledEvent {
+    pub raffle_id: u64,
+    pub refunds: Vec<(RaffleTicket, Decimal)>,
 }
 
 #[blueprint]
-#[types(AccountRewardsData, OrderRewardsData, String, Decimal, Vault)]
+#[events(RewardsClaimedEvent, RewardsFrozenEvent, RewardsUnfrozenEvent, PendingRewardsEvent, RaffleDrawnEvent, RaffleCancelledEvent)]
+#[types(AccountRewardsData, OrderRewardsData, VersionedAccountData, CampaignManagerData, CampaignData, ClaimFee, DelegateGrant, RaffleData, FaucetData, AccrualConfig, NonFungibleLocalId, String, Decimal, Vault)]
 mod dexter_claim_component {
     enable_method_auth! {
         roles {
             super_admin => updatable_by: [OWNER];
             admin => updatable_by: [OWNER, super_admin];
+            treasurer => updatable_by: [OWNER, super_admin];
+            predecessor => updatable_by: [OWNER];
         },
         methods {
             add_account_rewards => restrict_to: [admin, super_admin];
             add_orders_rewards => restrict_to: [admin, super_admin];
             add_rewards => restrict_to: [admin, super_admin];
+            airdrop => restrict_to: [admin, super_admin];
+            add_account_nft_rewards => restrict_to: [admin, super_admin];
+            remove_account_nft_rewards => restrict_to: [super_admin];
+            get_account_nft_rewards => PUBLIC;
+            create_raffle => restrict_to: [admin, super_admin];
+            add_raffle_tickets => restrict_to: [admin, super_admin];
+            close_raffle => restrict_to: [admin, super_admin];
+            draw_raffle => restrict_to: [admin, super_admin];
+            cancel_raffle => PUBLIC;
+            refund_raffle => restrict_to: [treasurer];
+            get_raffle => PUBLIC;
+            create_faucet => restrict_to: [admin, super_admin];
+            close_faucet => restrict_to: [super_admin];
+            claim_faucet => PUBLIC;
+            get_faucet => PUBLIC;
+            register_pair => restrict_to: [super_admin];
+            unregister_pair => restrict_to: [super_admin];
+            fund_accrual => restrict_to: [admin, super_admin];
+            record_fill => PUBLIC;
+            get_accrual_config => PUBLIC;
             remove_account_rewards => restrict_to: [super_admin];
             remove_orders_rewards => restrict_to: [super_admin];
             remove_rewards => restrict_to: [super_admin];
+            get_orders_rewards => PUBLIC;
+            create_campaign => restrict_to: [super_admin];
+            mint_campaign_manager_badge => restrict_to: [super_admin];
+            update_campaign_budget => restrict_to: [super_admin];
+            revoke_campaign_manager_badge => restrict_to: [super_admin];
+            add_campaign_account_rewards => PUBLIC;
+            add_campaign_orders_rewards => PUBLIC;
+            remove_campaign_account_rewards => PUBLIC;
+            remove_campaign_orders_rewards => PUBLIC;
+            freeze_rewards => restrict_to: [super_admin];
+            unfreeze_rewards => restrict_to: [super_admin];
+            return_account_nfts => restrict_to: [super_admin];
+            upgrade_account_data => restrict_to: [super_admin];
+            get_account_data => PUBLIC;
+            set_strict_claims => restrict_to: [super_admin];
             claim_rewards => PUBLIC;
+            claim_rewards_paged => PUBLIC;
+            claim_and_stake => PUBLIC;
+            claim_and_forward => PUBLIC;
+            convert_to_claim_tokens => PUBLIC;
+            redeem => PUBLIC;
+            get_claim_token_liability => PUBLIC;
+            set_payout_splits => PUBLIC;
+            grant_claim_delegate => PUBLIC;
+            revoke_claim_delegate => PUBLIC;
+            delegated_claim => PUBLIC;
+            claim_for_account => PUBLIC;
+            deliver_pending_rewards => PUBLIC;
+            migrate_to => restrict_to: [OWNER];
+            migrate_orders => restrict_to: [OWNER];
+            migrate_accounts => restrict_to: [OWNER];
+            accept_migrated_vaults => restrict_to: [predecessor];
+            accept_migrated_nft_manager => restrict_to: [predecessor];
+            accept_migrated_claim_tokens => restrict_to: [predecessor];
+            accept_migrated_allocations => restrict_to: [predecessor];
+            accept_migrated_orders => restrict_to: [predecessor];
+            accept_migrated_accounts => restrict_to: [predecessor];
+            update_name => restrict_to: [OWNER];
+            update_description => restrict_to: [OWNER];
+            update_icon_url => restrict_to: [OWNER];
+            update_tags => restrict_to: [OWNER];
+            set_dapp_definitions => restrict_to: [OWNER];
+            set_claimed_entities => restrict_to: [OWNER];
+            set_claim_fee => restrict_to: [super_admin];
+            remove_claim_fee => restrict_to: [super_admin];
+            withdraw_fees => restrict_to: [treasurer];
+            fund_fee_subsidy => restrict_to: [treasurer];
+            withdraw_fee_subsidy => restrict_to: [treasurer];
+            set_fee_subsidy_limits => restrict_to: [super_admin];
+            approve_validators => restrict_to: [super_admin];
+            revoke_validators => restrict_to: [super_admin];
+            set_restrict_validators => restrict_to: [super_admin];
+            add_forward_targets => restrict_to: [super_admin];
+            remove_forward_targets => restrict_to: [super_admin];
             deactivate => restrict_to: [super_admin];
             activate => restrict_to: [super_admin];
         }
     }
     struct DexterClaimComponent {
         pub account_rewards_nft_manager: ResourceManager,
+        pub account_data: KeyValueStore<NonFungibleLocalId, VersionedAccountData>, // KVS with the versioned data of account NFTs, such as their claim history and payout splits
         pub order_rewards: KeyValueStore<String, OrderRewardsData>, // KSV to store order rewards. Key is unique order id = Order receipt resource address +"#"+ Order receipt local id + "#"
         pub claim_vaults: KeyValueStore<ResourceAddress, Vault>,
+        pub claim_tokens: Vec<ResourceAddress>, // tokens with a vault in claim_vaults, needed to move all vaults during a migration
+        pub nft_authority_badge: Vault, // badge that can mint, burn and update account rewards NFTs next to this component. Handed over to the successor on migration
+        pub predecessor_nft_authority_badge: Option<Vault>, // nft authority badge of the predecessor, held after taking over its account rewards NFTs
+        pub migrated_to: Option<ComponentAddress>,
+        pub campaign_manager_badge_manager: ResourceManager,
+        pub campaigns: KeyValueStore<String, CampaignData>, // KVS to store partner campaigns. Key is the campaign id
+        pub campaign_manager_badge_counter: u64,
+        pub revoked_campaign_manager_badges: KeyValueStore<NonFungibleLocalId, bool>, // KVS with the campaign manager badges that can no longer manage their campaign
+        pub frozen_accounts: KeyValueStore<NonFungibleLocalId, u64>, // KVS of account NFT ids that can not claim. Value is the epoch in which the NFT was frozen
+        pub frozen_orders: KeyValueStore<String, u64>, // KVS of order ids (same keys as order_rewards) that can not claim. Value is the epoch in which the order was frozen
+        pub pending_rewards: KeyValueStore<ComponentAddress, HashMap<String, HashMap<ResourceAddress, Decimal>>>, // KVS to store rewards for accounts that refused the rewards NFT deposit
+        pub claim_fees: KeyValueStore<ResourceAddress, ClaimFee>, // KVS with the fee taken from claimed rewards per token
+        pub fee_vaults: KeyValueStore<ResourceAddress, Vault>, // KVS with the fees taken from claimed rewards per token, withdrawn by the treasurer
+        pub claim_token_managers: KeyValueStore<ResourceAddress, ResourceManager>, // KVS with the claim token resource per reward token
+        pub claim_token_rewards: KeyValueStore<ResourceAddress, ResourceAddress>, // KVS with the reward token per claim token resource
+        pub claim_token_liabilities: KeyValueStore<ResourceAddress, Decimal>, // KVS with the amount of reward tokens in claim_vaults that back claim tokens, per reward token
+        pub fee_subsidy_vault: Vault, // XRD used to pay transaction fees for claims
+        pub fee_subsidy_per_claim: Decimal,
+        pub fee_subsidy_per_account_epoch: Decimal,
+        pub fee_subsidy_usage: KeyValueStore<NonFungibleLocalId, (u64, Decimal)>, // KVS with the last epoch and the fee subsidy used in that epoch per account NFT
+        pub approved_validators: KeyValueStore<ComponentAddress, bool>, // KVS with validators that claimed XRD can be staked to if restrict_validators is true
+        pub restrict_validators: bool,
+        pub forward_targets: KeyValueStore<(ComponentAddress, String), bool>, // KVS with the component methods that claimed rewards can be forwarded to
+        pub claim_delegates: KeyValueStore<NonFungibleLocalId, DelegateGrant>, // KVS with the delegates that can claim on behalf of account NFT holders
+        pub raffles: KeyValueStore<u64, RaffleData>, // KVS with the raffle campaigns by raffle id
+        pub raffle_counter: u64,
+        pub faucets: KeyValueStore<u64, FaucetData>, // KVS with the faucet campaigns by faucet id
+        pub faucet_counter: u64,
+        pub faucet_claims: KeyValueStore<(u64, NonFungibleLocalId), bool>, // KVS with the account NFT ids that claimed from a faucet
+        pub accrual_configs: KeyValueStore<ResourceAddress, AccrualConfig>, // KVS with the on-ledger accrual configs by order receipt address
+        pub accrual_order_receipts: Vec<ResourceAddress>, // order receipts with an accrual config, needed to move the accrual configs during a migration
+        pub migrated_faucet_ids: Vec<(u64, u64)>, // (faucet id, faucet id in the successor) of the migrated faucets, needed to move the faucet claims of accounts
+        pub account_nft_rewards: KeyValueStore<NonFungibleLocalId, HashMap<String, HashMap<ResourceAddress, Vec<NonFungibleLocalId>>>>, // KVS with the non-fungible rewards of account NFTs. The rewarded NFTs are held in the claim vaults
+        pub strict_claims: bool, // if true, claims with an account NFT also require a proof of ownership of the related account
+        pub dapp_definition: Global<Account>,
         pub active: bool,
         pub env: String,
 
@@ -76,8 +393,68 @@ mod dexter_claim_component {
             admin_token_address: ResourceAddress,
             super_admin_token_address: ResourceAddress,
             owner_token_address: ResourceAddress,
+            soulbound: bool,
         ) -> Global<DexterClaimComponent> {
-            DexterClaimComponent::new_advanced(name, description, icon_url, rule!(require(admin_token_address)), rule!(require(super_admin_token_address)), rule!(require(owner_token_address)))
+            DexterClaimComponent::new_advanced(name, description, icon_url, rule!(require(admin_token_address)), rule!(require(super_admin_token_address)), rule!(require(owner_token_address)), soulbound)
+        }
+
+        // Creates owner, super_admin and admin badges for a new claim component and returns them with the component.
+        // The owner can mint new super_admin badges and super_admins can mint new admin badges.
+        pub fn new_with_badges(
+            name: String,
+            description: String,
+            icon_url: String,
+            soulbound: bool,
+        ) -> (Global<DexterClaimComponent>, Bucket, Bucket, Bucket) {
+            let owner_badge: Bucket = ResourceBuilder::new_fungible(OwnerRole::None)
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata(metadata! {
+                    init{
+                        "name" => format!("{} Owner Badge",name.clone()), locked;
+                        "description" => format!("Owner badge for {}.", name.clone()), locked;
+                        "icon_url" => Url::of(icon_url.clone()), locked;
+                        "tags" => vec!["Badge"], locked;
+                    }
+                })
+                .mint_initial_supply(1)
+                .into();
+            let owner_rule = rule!(require(owner_badge.resource_address()));
+            let super_admin_badge: Bucket = ResourceBuilder::new_fungible(OwnerRole::Updatable(owner_rule.clone()))
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata(metadata! {
+                    init{
+                        "name" => format!("{} Super Admin Badge",name.clone()), updatable;
+                        "description" => format!("Super admin badge for {}.", name.clone()), updatable;
+                        "icon_url" => Url::of(icon_url.clone()), updatable;
+                        "tags" => vec!["Badge"], updatable;
+                    }
+                })
+                .mint_roles(mint_roles! {
+                    minter => owner_rule.clone();
+                    minter_updater => owner_rule.clone();
+                })
+                .mint_initial_supply(1)
+                .into();
+            let super_admin_rule = rule!(require(super_admin_badge.resource_address()));
+            let admin_badge: Bucket = ResourceBuilder::new_fungible(OwnerRole::Updatable(owner_rule.clone()))
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata(metadata! {
+                    init{
+                        "name" => format!("{} Admin Badge",name.clone()), updatable;
+                        "description" => format!("Admin badge for {}.", name.clone()), updatable;
+                        "icon_url" => Url::of(icon_url.clone()), updatable;
+                        "tags" => vec!["Badge"], updatable;
+                    }
+                })
+                .mint_roles(mint_roles! {
+                    minter => super_admin_rule.clone();
+                    minter_updater => owner_rule.clone();
+                })
+                .mint_initial_supply(1)
+                .into();
+            let admin_rule = rule!(require(admin_badge.resource_address()));
+            let new_component = DexterClaimComponent::new_advanced(name, description, icon_url, admin_rule, super_admin_rule, owner_rule, soulbound);
+            (new_component, owner_badge, super_admin_badge, admin_badge)
         }
 
         pub fn new_advanced(
@@ -87,45 +464,151 @@ mod dexter_claim_component {
             admin_rule: AccessRule,
             super_admin_rule: AccessRule,
             owner_rule: AccessRule,
+            soulbound: bool,
         ) -> Global<DexterClaimComponent> {
             let (address_reservation, component_address) =
                 Runtime::allocate_component_address(<DexterClaimComponent>::blueprint_id());
             let require_component_rule = rule!(require(global_caller(component_address)));
+            // the nft authority badge allows a successor component to keep managing the account rewards NFTs after a migration
+            let nft_authority_badge: Bucket = ResourceBuilder::new_fungible(OwnerRole::Updatable(owner_rule.clone()))
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata(metadata! {
+                    init{
+                        "name" => format!("{} NFT Authority Badge",name.clone()), updatable;
+                        "description" => "A badge that can mint, burn and update the account rewards NFTs of a claim component.", updatable;
+                        "tags" => vec!["Badge"], updatable;
+                    }
+                })
+                .mint_initial_supply(1)
+                .into();
+            let nft_authority_rule = rule!(require(global_caller(component_address)) || require(nft_authority_badge.resource_address()));
+            let nft_metadata_rule = DexterClaimComponent::create_any_of_rule(super_admin_rule.clone(), nft_authority_rule.clone());
+            // the component can update the metadata of itself, the account rewards NFT and the dapp definition through its owner methods
+            let owner_metadata_rule = DexterClaimComponent::create_any_of_rule(owner_rule.clone(), require_component_rule.clone());
+            let super_admin_metadata_rule = DexterClaimComponent::create_any_of_rule(super_admin_rule.clone(), require_component_rule.clone());
             // set up a dapp definition account for the pair
             let dapp_def_account =
                 Blueprint::<Account>::create_advanced(OwnerRole::Updatable(rule!(allow_all)), None);
             let dapp_def_address = GlobalAddress::from(dapp_def_account.address());
             // metadata and owner for the dapp definition are added later in the function after the entities are created.
 
+            // soulbound NFTs can only be moved by the component, or recalled by a super_admin to return them to their account.
+            let (nft_description, withdraw_rule, recall_rule) = if soulbound {
+                ("An NFT that keeps track of rewards related to an account. The NFT can not be transferred and the rewards in this NFT will always relate to the specified account.", nft_authority_rule.clone(), super_admin_rule.clone())
+            } else {
+                ("An NFT that keeps track of rewards related to an account. Although the NFT can be transferred between accounts, the rewards in this NFT will always relate to the specified account.", rule!(allow_all), rule!(deny_all))
+            };
+
             let account_rewards_nft_manager = 
                 ResourceBuilder::new_string_non_fungible_with_registered_type::<AccountRewardsData>(OwnerRole::Updatable(super_admin_rule.clone()))
                 .metadata(metadata! {
+                    roles {
+                        metadata_setter => nft_metadata_rule;
+                        metadata_setter_updater => super_admin_rule.clone();
+                        metadata_locker => super_admin_rule.clone();
+                        metadata_locker_updater => super_admin_rule.clone();
+                    },
                     init{
                         "name" => format!("{} NFT",name.clone()), updatable;
-                        "description" => "An NFT that keeps track of rewards related to an account. Although the NFT can be transferred between accounts, the rewards in this NFT will always relate to the specified account.", updatable;
+                        "description" => nft_description, updatable;
                         "icon_url" => Url::of(icon_url.clone()), updatable;
                         "tags" => vec!["Rewards"], updatable;
                         "dapp_definitions" => vec![dapp_def_address.clone()], updatable;
                     }
                 })
                 .non_fungible_data_update_roles(non_fungible_data_update_roles! {
-                    non_fungible_data_updater => require_component_rule.clone();
+                    non_fungible_data_updater => nft_authority_rule.clone();
                     non_fungible_data_updater_updater => rule!(deny_all);
                 })
+                .mint_roles(mint_roles! {
+                    minter => nft_authority_rule.clone();
+                    minter_updater => rule!(deny_all);
+                })
+                .burn_roles(burn_roles! {
+                    burner => nft_authority_rule.clone();
+                    burner_updater => rule!(deny_all);
+                })
+                .withdraw_roles(withdraw_roles! {
+                    withdrawer => withdraw_rule;
+                    withdrawer_updater => rule!(deny_all);
+                })
+                .recall_roles(recall_roles! {
+                    recaller => recall_rule;
+                    recaller_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
+
+            let campaign_manager_badge_manager =
+                ResourceBuilder::new_integer_non_fungible_with_registered_type::<CampaignManagerData>(OwnerRole::Updatable(super_admin_rule.clone()))
+                .metadata(metadata! {
+                    roles {
+                        metadata_setter => super_admin_metadata_rule.clone();
+                        metadata_setter_updater => super_admin_rule.clone();
+                        metadata_locker => super_admin_rule.clone();
+                        metadata_locker_updater => super_admin_rule.clone();
+                    },
+                    init{
+                        "name" => format!("{} Campaign Manager Badge",name.clone()), updatable;
+                        "description" => "A badge that allows a partner to add and remove rewards for a single campaign, within the budget of that campaign.", updatable;
+                        "icon_url" => Url::of(icon_url.clone()), updatable;
+                        "tags" => vec!["Rewards", "Badge"], updatable;
+                        "dapp_definitions" => vec![dapp_def_address.clone()], updatable;
+                    }
+                })
                 .mint_roles(mint_roles! {
                     minter => require_component_rule.clone();
                     minter_updater => rule!(deny_all);
                 })
                 .burn_roles(burn_roles! {
-                    burner => require_component_rule.clone();
+                    burner => super_admin_rule.clone();
                     burner_updater => rule!(deny_all);
                 })
+                .recall_roles(recall_roles! {
+                    recaller => super_admin_rule.clone();
+                    recaller_updater => rule!(deny_all);
+                })
                 .create_with_no_initial_supply();
 
             let new_component = Self {
                 account_rewards_nft_manager,
+                account_data: KeyValueStore::new(),
                 order_rewards: KeyValueStore::new(),
                 claim_vaults: KeyValueStore::new(),
+                claim_tokens: vec![],
+                nft_authority_badge: Vault::with_bucket(nft_authority_badge),
+                predecessor_nft_authority_badge: None,
+                migrated_to: None,
+                campaign_manager_badge_manager,
+                campaigns: KeyValueStore::new(),
+                campaign_manager_badge_counter: 0,
+                revoked_campaign_manager_badges: KeyValueStore::new(),
+                frozen_accounts: KeyValueStore::new(),
+                frozen_orders: KeyValueStore::new(),
+                pending_rewards: KeyValueStore::new(),
+                claim_fees: KeyValueStore::new(),
+                fee_vaults: KeyValueStore::new(),
+                claim_token_managers: KeyValueStore::new(),
+                claim_token_rewards: KeyValueStore::new(),
+                claim_token_liabilities: KeyValueStore::new(),
+                fee_subsidy_vault: Vault::new(XRD),
+                fee_subsidy_per_claim: Decimal::ZERO,
+                fee_subsidy_per_account_epoch: Decimal::ZERO,
+                fee_subsidy_usage: KeyValueStore::new(),
+                approved_validators: KeyValueStore::new(),
+                restrict_validators: false,
+                forward_targets: KeyValueStore::new(),
+                claim_delegates: KeyValueStore::new(),
+                raffles: KeyValueStore::new(),
+                raffle_counter: 0,
+                faucets: KeyValueStore::new(),
+                faucet_counter: 0,
+                faucet_claims: KeyValueStore::new(),
+                accrual_configs: KeyValueStore::new(),
+                accrual_order_receipts: vec![],
+                migrated_faucet_ids: vec![],
+                account_nft_rewards: KeyValueStore::new(),
+                strict_claims: false,
+                dapp_definition: dapp_def_account.clone(),
                 active: true,
                 env: String::from(""),
             }
@@ -135,8 +618,16 @@ mod dexter_claim_component {
             .roles(roles!(
                 super_admin => super_admin_rule.clone();
                 admin => admin_rule.clone();
+                treasurer => super_admin_rule.clone();
+                predecessor => rule!(deny_all);
             ))
             .metadata(metadata! {
+              roles {
+                metadata_setter => owner_metadata_rule.clone();
+                metadata_setter_updater => owner_rule.clone();
+                metadata_locker => owner_rule.clone();
+                metadata_locker_updater => owner_rule.clone();
+              },
               init {
                 "name" => name.clone(), updatable;
                 "description" => description.clone(), updatable;
@@ -147,57 +638,1262 @@ mod dexter_claim_component {
             })
             .globalize();
 
-            // set dapp definition metadata and owner
-            dapp_def_account.set_metadata("account_type", String::from("dapp definition"));
-            dapp_def_account.set_metadata("name", name.clone());
-            dapp_def_account.set_metadata(
-                "description",
-                description.clone(),
-            );
-            dapp_def_account.set_metadata(
-                "icon_url",
-                Url::of(icon_url.clone()),
-                // Url::of("https://dexteronradix.com/logo_icon.svg"),
-            );
-            dapp_def_account.set_metadata(
-                "claimed_entities",
-                vec![GlobalAddress::from(component_address.clone()), account_rewards_nft_manager.address().into()],
-            );
-            dapp_def_account.set_owner_role(owner_rule.clone());
+            // set dapp definition metadata and owner
+            dapp_def_account.set_metadata("account_type", String::from("dapp definition"));
+            dapp_def_account.set_metadata("name", name.clone());
+            dapp_def_account.set_metadata(
+                "description",
+                description.clone(),
+            );
+            dapp_def_account.set_metadata(
+                "icon_url",
+                Url::of(icon_url.clone()),
+                // Url::of("https://dexteronradix.com/logo_icon.svg"),
+            );
+            dapp_def_account.set_metadata(
+                "claimed_entities",
+                vec![GlobalAddress::from(component_address.clone()), account_rewards_nft_manager.address().into(), campaign_manager_badge_manager.address().into()],
+            );
+            dapp_def_account.set_owner_role(owner_metadata_rule.clone());
+
+            new_component
+        }
+
+        pub fn update_name(&mut self, name: String) {
+            Runtime::global_component().set_metadata("name", name.clone());
+            let nft_manager = self.account_rewards_nft_manager;
+            self.get_nft_authority_vault().as_fungible().authorize_with_amount(1, || nft_manager.set_metadata("name", format!("{} NFT", name.clone())));
+            self.campaign_manager_badge_manager.set_metadata("name", format!("{} Campaign Manager Badge", name.clone()));
+            self.dapp_definition.set_metadata("name", name);
+        }
+
+        pub fn update_description(&mut self, description: String) {
+            Runtime::global_component().set_metadata("description", description.clone());
+            let nft_manager = self.account_rewards_nft_manager;
+            self.get_nft_authority_vault().as_fungible().authorize_with_amount(1, || nft_manager.set_metadata("description", description.clone()));
+            self.campaign_manager_badge_manager.set_metadata("description", description.clone());
+            self.dapp_definition.set_metadata("description", description);
+        }
+
+        pub fn update_icon_url(&mut self, icon_url: String) {
+            Runtime::global_component().set_metadata("icon_url", Url::of(icon_url.clone()));
+            let nft_manager = self.account_rewards_nft_manager;
+            self.get_nft_authority_vault().as_fungible().authorize_with_amount(1, || nft_manager.set_metadata("icon_url", Url::of(icon_url.clone())));
+            self.campaign_manager_badge_manager.set_metadata("icon_url", Url::of(icon_url.clone()));
+            self.dapp_definition.set_metadata("icon_url", Url::of(icon_url));
+        }
+
+        pub fn update_tags(&mut self, tags: Vec<String>) {
+            Runtime::global_component().set_metadata("tags", tags.clone());
+            let nft_manager = self.account_rewards_nft_manager;
+            self.get_nft_authority_vault().as_fungible().authorize_with_amount(1, || nft_manager.set_metadata("tags", tags.clone()));
+            self.campaign_manager_badge_manager.set_metadata("tags", tags.clone());
+            self.dapp_definition.set_metadata("tags", tags);
+        }
+
+        // The first dapp definition becomes the account that the other owner methods write metadata to.
+        // Its owner role must allow this component to set metadata, like the dapp definition created in new_advanced.
+        pub fn set_dapp_definitions(&mut self, dapp_definitions: Vec<ComponentAddress>) {
+            assert!(dapp_definitions.len() > 0, "At least one dapp definition is required.");
+            let dapp_definition_addresses: Vec<GlobalAddress> = dapp_definitions.iter().map(|dapp_definition| GlobalAddress::from(dapp_definition.clone())).collect();
+            self.dapp_definition = Global::<Account>::from(dapp_definitions[0].clone());
+            Runtime::global_component().set_metadata("dapp_definition", dapp_definition_addresses[0].clone());
+            let nft_manager = self.account_rewards_nft_manager;
+            self.get_nft_authority_vault().as_fungible().authorize_with_amount(1, || nft_manager.set_metadata("dapp_definitions", dapp_definition_addresses.clone()));
+            self.campaign_manager_badge_manager.set_metadata("dapp_definitions", dapp_definition_addresses.clone());
+            for token_address in self.claim_tokens.iter() {
+                if let Some(claim_token_manager) = self.claim_token_managers.get(token_address) {
+                    let claim_token_manager = claim_token_manager.clone();
+                    self.get_nft_authority_vault().as_fungible().authorize_with_amount(1, || claim_token_manager.set_metadata("dapp_definitions", dapp_definition_addresses.clone()));
+                }
+            }
+        }
+
+        pub fn set_claimed_entities(&mut self, claimed_entities: Vec<GlobalAddress>) {
+            self.dapp_definition.set_metadata("claimed_entities", claimed_entities);
+        }
+
+        pub fn add_account_rewards(
+            &mut self,
+            reward_name: String,
+            reward_token: ResourceAddress,
+            account_rewards: Vec<(ComponentAddress,Decimal)>,
+            rewards_bucket: Bucket,
+        ) -> (Bucket, RewardsReport) {
+            self.add_rewards(reward_name, reward_token, account_rewards, String::from(""), rewards_bucket)
+        }
+
+         pub fn add_orders_rewards(
+            &mut self,
+            reward_name: String,
+            reward_token: ResourceAddress,
+            orders_rewards_string: String,
+            rewards_bucket: Bucket,
+        ) -> (Bucket, RewardsReport) {
+            self.add_rewards(reward_name, reward_token, vec![], orders_rewards_string, rewards_bucket)
+        }
+
+        pub fn add_rewards(
+            &mut self,
+            reward_name: String,
+            reward_token: ResourceAddress,
+            account_rewards: Vec<(ComponentAddress,Decimal)>,
+            orders_rewards_string: String,
+            rewards_bucket: Bucket,
+        ) -> (Bucket, RewardsReport) {
+            self.assert_not_campaign_reward_name(&reward_name);
+            self.add_named_rewards(reward_name, reward_token, account_rewards, orders_rewards_string, rewards_bucket)
+        }
+
+        // Deposits tokens directly into the accounts. Tokens for accounts that refuse the deposit are added as claimable airdrop rewards instead.
+        pub fn airdrop(
+            &mut self,
+            mut airdrop_bucket: Bucket,
+            account_amounts: Vec<(ComponentAddress,Decimal)>,
+        ) -> Bucket {
+            assert!(self.active,"Component has been deactivated.");
+            let airdrop_token = airdrop_bucket.resource_address();
+            self.assert_fungible_reward_token(&airdrop_token);
+            let mut refused_account_amounts: Vec<(ComponentAddress,Decimal)> = vec![];
+            for (account_address, amount) in account_amounts {
+                assert!(airdrop_bucket.amount() >= amount, "Not enough tokens sent in airdrop bucket. Needed {:?} for account {:?}, but found only {:?}.", amount, account_address, airdrop_bucket.amount());
+                let account_component: Global<AnyComponent> = Global::from(account_address);
+                let returned_bucket: Option<Bucket> = account_component.call::<(Bucket,Option<ResourceOrNonFungible>),_>("try_deposit_or_refund", &(airdrop_bucket.take(amount), None));
+                if let Some(returned_tokens) = returned_bucket {
+                    airdrop_bucket.put(returned_tokens);
+                    refused_account_amounts.push((account_address, amount));
+                }
+            }
+            if refused_account_amounts.len() > 0 {
+                let (return_bucket, _report) = self.add_rewards(String::from("Airdrop"), airdrop_token, refused_account_amounts, String::from(""), airdrop_bucket);
+                return return_bucket;
+            }
+            airdrop_bucket
+        }
+
+        // Adds non-fungible rewards for accounts. The rewarded NFTs are taken from the rewards bucket and can be claimed by id.
+        pub fn add_account_nft_rewards(
+            &mut self,
+            reward_name: String,
+            account_rewards: Vec<(ComponentAddress,Vec<NonFungibleLocalId>)>,
+            rewards_bucket: Bucket,
+        ) -> Bucket {
+            assert!(self.active,"Component has been deactivated.");
+            self.assert_not_campaign_reward_name(&reward_name);
+            let reward_token = rewards_bucket.resource_address();
+            assert!(!reward_token.is_fungible(), "Rewards Bucket must contain non-fungible tokens.");
+            let mut rewards_bucket = rewards_bucket.as_non_fungible();
+            for (account_address, nft_ids) in account_rewards {
+                let account_id = NonFungibleLocalId::string(self.create_account_id(&account_address)).expect(&format!("Could not convert {:?} into a valid NFT ID", account_address));
+                // non-fungible rewards are kept by account NFT id, so they can also be recorded for accounts that refuse the NFT deposit.
+                // Those accounts can claim them with claim_for_account, or with the NFT once it has been delivered.
+                if !self.account_rewards_nft_manager.non_fungible_exists(&account_id) && !self.mint_account_nft(&account_address, &account_id) {
+                    Runtime::emit_event(PendingRewardsEvent {
+                        account_address,
+                        reward_name: reward_name.clone(),
+                        reward_token,
+                        amount: Decimal::from(nft_ids.len() as u64),
+                    });
+                }
+                let nft_ids_set: IndexSet<NonFungibleLocalId> = nft_ids.iter().cloned().collect();
+                assert!(nft_ids_set.len() == nft_ids.len(), "Duplicate NFT ids found in rewards for account {:?}.", account_address);
+                let reward_nfts = rewards_bucket.take_non_fungibles(&nft_ids_set);
+                self.deposit_to_claim_vault(reward_nfts.into());
+                let mut nft_rewards = self.account_nft_rewards.get(&account_id).map(|rewards| rewards.clone()).unwrap_or(HashMap::new());
+                nft_rewards.entry(reward_name.clone()).or_insert(HashMap::new()).entry(reward_token).or_insert(vec![]).extend(nft_ids);
+                self.account_nft_rewards.insert(account_id, nft_rewards);
+            }
+            rewards_bucket.into()
+        }
+
+        // Removes the non-fungible rewards of the reward name for the accounts and returns the removed NFTs.
+        pub fn remove_account_nft_rewards(
+            &mut self,
+            reward_name: String,
+            accounts: Vec<ComponentAddress>,
+        ) -> Vec<Bucket> {
+            self.assert_not_campaign_reward_name(&reward_name);
+            let mut nft_totals: HashMap<ResourceAddress, Vec<NonFungibleLocalId>> = HashMap::new();
+            for account_address in accounts {
+                let account_id = NonFungibleLocalId::string(self.create_account_id(&account_address)).expect(&format!("Could not convert {:?} into a valid NFT ID", account_address));
+                let mut nft_rewards = match self.account_nft_rewards.get(&account_id) {
+                    Some(nft_rewards) => nft_rewards.clone(),
+                    None => continue,
+                };
+                if let Some(name_rewards) = nft_rewards.remove(&reward_name) {
+                    for (reward_token, nft_ids) in name_rewards {
+                        nft_totals.entry(reward_token).or_insert(vec![]).extend(nft_ids);
+                    }
+                }
+                if nft_rewards.len() > 0 {
+                    self.account_nft_rewards.insert(account_id, nft_rewards);
+                } else {
+                    self.account_nft_rewards.remove(&account_id);
+                }
+            }
+            self.withdraw_nft_rewards(nft_totals)
+        }
+
+        pub fn get_account_nft_rewards(&self, account_address: ComponentAddress) -> HashMap<String, HashMap<ResourceAddress, Vec<NonFungibleLocalId>>> {
+            let account_id = NonFungibleLocalId::string(self.create_account_id(&account_address)).expect(&format!("Could not convert {:?} into a valid NFT ID", account_address));
+            self.account_nft_rewards.get(&account_id).map(|rewards| rewards.clone()).unwrap_or(HashMap::new())
+        }
+
+        pub fn remove_account_rewards(
+            &mut self, 
+            reward_name: String,
+            reward_token: ResourceAddress,
+            account_rewards: Vec<(ComponentAddress,Decimal)>
+        ) -> (Bucket, RewardsReport) {
+            self.remove_rewards(reward_name, reward_token, account_rewards, String::from(""))
+        }
+        
+        pub fn remove_orders_rewards(
+            &mut self, 
+            reward_name: String,
+            reward_token: ResourceAddress,
+            orders_rewards_string: String
+        ) -> (Bucket, RewardsReport) {
+            self.remove_rewards(reward_name, reward_token, vec![], orders_rewards_string)
+        }
+        
+        pub fn remove_rewards(
+            &mut self, 
+            reward_name: String,
+            reward_token: ResourceAddress,
+            account_rewards: Vec<(ComponentAddress,Decimal)>,
+            orders_rewards_string: String
+        ) -> (Bucket, RewardsReport) {
+            self.assert_not_campaign_reward_name(&reward_name);
+            self.remove_named_rewards(reward_name, reward_token, account_rewards, orders_rewards_string)
+        }
+
+        pub fn get_orders_rewards(&self, order_ids: Vec<String>) -> Vec<OrderRewardsData> {
+            let mut orders_rewards: Vec<OrderRewardsData> = vec![];
+            for order_id in order_ids {
+                if let Some(order_rewards_data) = self.order_rewards.get(&order_id) {
+                    orders_rewards.push(order_rewards_data.clone());
+                }
+            }
+            orders_rewards
+        }
+
+        pub fn create_campaign(
+            &mut self,
+            campaign_id: String,
+            reward_token: ResourceAddress,
+            budget: Decimal,
+        ) -> Bucket {
+            assert!(campaign_id.len() > 0, "Campaign id can not be empty.");
+            assert!(self.campaigns.get(&campaign_id).is_none(), "Campaign {:?} already exists.", campaign_id);
+            assert!(budget >= Decimal::ZERO, "Campaign budget can not be negative.");
+            self.assert_fungible_reward_token(&reward_token);
+            self.campaigns.insert(
+                campaign_id.clone(),
+                CampaignData {
+                    campaign_id: campaign_id.clone(),
+                    reward_token,
+                    budget,
+                    allocated: Decimal::ZERO,
+                },
+            );
+            self.mint_campaign_manager_badge(campaign_id)
+        }
+
+        pub fn mint_campaign_manager_badge(&mut self, campaign_id: String) -> Bucket {
+            assert!(self.campaigns.get(&campaign_id).is_some(), "Campaign {:?} does not exist.", campaign_id);
+            self.campaign_manager_badge_counter += 1;
+            self.campaign_manager_badge_manager.mint_non_fungible(
+                &NonFungibleLocalId::integer(self.campaign_manager_badge_counter),
+                CampaignManagerData {
+                    name: format!("{} Manager", campaign_id),
+                    campaign_id,
+                },
+            )
+        }
+
+        pub fn update_campaign_budget(&mut self, campaign_id: String, budget: Decimal) {
+            let mut campaign = self.campaigns.get_mut(&campaign_id).expect(&format!("Campaign {:?} does not exist.", campaign_id));
+            assert!(budget >= campaign.allocated, "Campaign budget can not be less than the rewards already allocated ({:?}).", campaign.allocated);
+            campaign.budget = budget;
+        }
+
+        // A revoked badge can no longer add or remove rewards for its campaign, also when it can not be recalled from the partner.
+        pub fn revoke_campaign_manager_badge(&mut self, badge_id: NonFungibleLocalId) {
+            assert!(self.campaign_manager_badge_manager.non_fungible_exists(&badge_id), "Campaign manager badge {:?} does not exist.", badge_id);
+            self.revoked_campaign_manager_badges.insert(badge_id, true);
+        }
+
+        pub fn add_campaign_account_rewards(
+            &mut self,
+            campaign_badge: NonFungibleProof,
+            account_rewards: Vec<(ComponentAddress,Decimal)>,
+            rewards_bucket: Bucket,
+        ) -> (Bucket, RewardsReport) {
+            self.add_campaign_rewards(campaign_badge, account_rewards, String::from(""), rewards_bucket)
+        }
+
+        pub fn add_campaign_orders_rewards(
+            &mut self,
+            campaign_badge: NonFungibleProof,
+            orders_rewards_string: String,
+            rewards_bucket: Bucket,
+        ) -> (Bucket, RewardsReport) {
+            self.add_campaign_rewards(campaign_badge, vec![], orders_rewards_string, rewards_bucket)
+        }
+
+        pub fn remove_campaign_account_rewards(
+            &mut self,
+            campaign_badge: NonFungibleProof,
+            account_rewards: Vec<(ComponentAddress,Decimal)>,
+        ) -> (Bucket, RewardsReport) {
+            self.remove_campaign_rewards(campaign_badge, account_rewards, String::from(""))
+        }
+
+        pub fn remove_campaign_orders_rewards(
+            &mut self,
+            campaign_badge: NonFungibleProof,
+            orders_rewards_string: String,
+        ) -> (Bucket, RewardsReport) {
+            self.remove_campaign_rewards(campaign_badge, vec![], orders_rewards_string)
+        }
+
+        pub fn freeze_rewards(&mut self, account_ids: Vec<NonFungibleLocalId>, order_ids: Vec<String>) {
+            let current_epoch = Runtime::current_epoch().number();
+            for account_id in account_ids.clone() {
+                self.frozen_accounts.insert(account_id, current_epoch);
+            }
+            for order_id in order_ids.clone() {
+                self.frozen_orders.insert(order_id, current_epoch);
+            }
+            Runtime::emit_event(RewardsFrozenEvent { account_ids, order_ids });
+        }
+
+        pub fn unfreeze_rewards(&mut self, account_ids: Vec<NonFungibleLocalId>, order_ids: Vec<String>) {
+            for account_id in account_ids.iter() {
+                self.frozen_accounts.remove(account_id);
+            }
+            for order_id in order_ids.iter() {
+                self.frozen_orders.remove(order_id);
+            }
+            Runtime::emit_event(RewardsUnfrozenEvent { account_ids, order_ids });
+        }
+
+        pub fn return_account_nfts(&mut self, account_address: ComponentAddress, account_nfts: Bucket) -> Option<Bucket> {
+            assert!(account_nfts.resource_address() == self.account_rewards_nft_manager.address(), "Only Dexter Claim NFTs can be returned to accounts.");
+            let account_id = NonFungibleLocalId::string(self.create_account_id(&account_address)).expect(&format!("Could not convert {:?} into a valid NFT ID", account_address));
+            for nft_id in account_nfts.as_non_fungible().non_fungible_local_ids() {
+                assert!(nft_id == account_id, "NFT {:?} does not relate to account {:?}.", nft_id, account_address);
+            }
+            let account_component: Global<AnyComponent> = Global::from(account_address);
+            account_component.call::<(Bucket,Option<ResourceOrNonFungible>),_>("try_deposit_or_refund", &(account_nfts, None))
+        }
+
+        pub fn upgrade_account_data(&mut self, account_ids: Vec<NonFungibleLocalId>) {
+            for account_id in account_ids {
+                if self.account_data.get(&account_id).is_some() {
+                    let account_data = self.load_account_data(&account_id);
+                    self.save_account_data(&account_id, account_data);
+                }
+            }
+        }
+
+        pub fn get_account_data(&self, account_id: NonFungibleLocalId) -> AccountDataV2 {
+            self.load_account_data(&account_id)
+        }
+
+        pub fn set_strict_claims(&mut self, strict_claims: bool) {
+            self.strict_claims = strict_claims;
+        }
+
+        pub fn claim_rewards(
+            &mut self,
+            reward_nft_proofs: Vec<NonFungibleProof>,
+            orders_proofs: Vec<NonFungibleProof>,
+        ) -> Vec<Bucket> {
+            // info!("Starting to claim rewards!");
+            assert!(self.active,"Component has been deactivated.");
+            let mut token_totals: HashMap<ResourceAddress, Decimal> = HashMap::new();
+            let mut claimed_totals: HashMap<ResourceAddress, (Decimal, Decimal)> = HashMap::new();
+            let (account_ids, mut return_buckets) = self.collect_account_claims(reward_nft_proofs, &mut token_totals, true, &mut claimed_totals);
+            if let Some(account_id) = account_ids.first() {
+                self.lock_fee_subsidy(account_id);
+            }
+            // info!("Handled accounts claims");
+
+            // info!("Starting to handle order claims");
+            self.collect_order_claims(orders_proofs, &mut token_totals);
+            // info!("Handled orders claims");
+            return_buckets.extend(self.pay_out_rewards(token_totals, &mut claimed_totals));
+            return_buckets
+        }
+
+        // Claims rewards and stakes the XRD rewards to the validator. Returns the stake units and the other claimed rewards.
+        pub fn claim_and_stake(
+            &mut self,
+            reward_nft_proofs: Vec<NonFungibleProof>,
+            orders_proofs: Vec<NonFungibleProof>,
+            validator: ComponentAddress,
+        ) -> Vec<Bucket> {
+            if self.restrict_validators {
+                assert!(self.approved_validators.get(&validator).is_some(), "Validator {:?} is not approved for staking claimed rewards.", validator);
+            }
+            let claimed_buckets = self.claim_rewards(reward_nft_proofs, orders_proofs);
+            let mut return_buckets: Vec<Bucket> = vec![];
+            for claimed_bucket in claimed_buckets {
+                if claimed_bucket.resource_address() == XRD && claimed_bucket.amount() > Decimal::ZERO {
+                    let validator_component: Global<AnyComponent> = Global::from(validator);
+                    return_buckets.push(validator_component.call::<(Bucket,),Bucket>("stake", &(claimed_bucket,)));
+                } else {
+                    return_buckets.push(claimed_bucket);
+                }
+            }
+            return_buckets
+        }
+
+        // Claims rewards and calls a whitelisted component method with the claimed rewards of the forward token.
+        // Returns the result of the called method and the other claimed rewards.
+        pub fn claim_and_forward(
+            &mut self,
+            reward_nft_proofs: Vec<NonFungibleProof>,
+            orders_proofs: Vec<NonFungibleProof>,
+            target_component: ComponentAddress,
+            target_method: String,
+            forward_token: ResourceAddress,
+        ) -> (ScryptoValue, Vec<Bucket>) {
+            assert!(self.forward_targets.get(&(target_component, target_method.clone())).is_some(), "Method {:?} of component {:?} is not whitelisted for forwarding claimed rewards.", target_method, target_component);
+            let claimed_buckets = self.claim_rewards(reward_nft_proofs, orders_proofs);
+            let mut forward_bucket: Option<Bucket> = None;
+            let mut return_buckets: Vec<Bucket> = vec![];
+            for claimed_bucket in claimed_buckets {
+                if claimed_bucket.resource_address() == forward_token && forward_bucket.is_none() {
+                    forward_bucket = Some(claimed_bucket);
+                } else {
+                    return_buckets.push(claimed_bucket);
+                }
+            }
+            let forward_bucket = forward_bucket.expect(&format!("No rewards found for token {:?} to forward.", forward_token));
+            let target: Global<AnyComponent> = Global::from(target_component);
+            let target_result = target.call::<(Bucket,),ScryptoValue>(&target_method, &(forward_bucket,));
+            (target_result, return_buckets)
+        }
+
+        // Converts unclaimed rewards into claim tokens that can be transferred and redeemed 1:1 for the reward tokens.
+        // The claim fee is taken when converting, so the reward tokens that stay in the claim vaults back the claim tokens exactly.
+        pub fn convert_to_claim_tokens(
+            &mut self,
+            reward_nft_proofs: Vec<NonFungibleProof>,
+            orders_proofs: Vec<NonFungibleProof>,
+        ) -> Vec<Bucket> {
+            assert!(self.active,"Component has been deactivated.");
+            let mut token_totals: HashMap<ResourceAddress, Decimal> = HashMap::new();
+            // non-fungible rewards can not be converted and are returned as they are
+            let (_account_ids, mut claim_token_buckets) = self.collect_account_claims(reward_nft_proofs, &mut token_totals, false, &mut HashMap::new());
+            self.collect_order_claims(orders_proofs, &mut token_totals);
+            for (token_address, token_reward) in token_totals {
+                let claim_token_manager = self.get_or_create_claim_token_manager(&token_address);
+                let fee_amount = self.calculate_claim_fee(&token_address, token_reward);
+                let mut net_reward = token_reward;
+                if fee_amount > Decimal::ZERO {
+                    let fee_bucket = self.claim_vaults.get_mut(&token_address).expect("Could not find claim vault for converted rewards.").take_advanced(fee_amount, WithdrawStrategy::Rounded(RoundingMode::ToZero));
+                    net_reward = net_reward.checked_sub(fee_bucket.amount()).expect("Could not subtract claim fee from converted rewards.");
+                    self.deposit_to_fee_vault(fee_bucket);
+                }
+                let liability = self.claim_token_liabilities.get(&token_address).map(|liability| liability.clone()).unwrap_or(Decimal::ZERO);
+                self.claim_token_liabilities.insert(token_address, liability.checked_add(net_reward).expect("Could not add claim tokens to claim token liabilities."));
+                claim_token_buckets.push(self.get_nft_authority_vault().as_fungible().authorize_with_amount(1, || claim_token_manager.mint(net_reward)));
+            }
+            claim_token_buckets
+        }
+
+        // Redeems claim tokens 1:1 for the reward tokens that back them. Claim tokens of a predecessor can be redeemed after a migration.
+        pub fn redeem(&mut self, claim_token_buckets: Vec<Bucket>) -> Vec<Bucket> {
+            assert!(self.active,"Component has been deactivated.");
+            let mut return_buckets: Vec<Bucket> = vec![];
+            for claim_token_bucket in claim_token_buckets {
+                let token_address = self.claim_token_rewards.get(&claim_token_bucket.resource_address()).expect("Only claim tokens of this component can be redeemed.").clone();
+                let redeem_amount = claim_token_bucket.amount();
+                self.get_nft_authority_vault().as_fungible().authorize_with_amount(1, || claim_token_bucket.burn());
+                let liability = self.claim_token_liabilities.get(&token_address).map(|liability| liability.clone()).unwrap_or(Decimal::ZERO);
+                self.claim_token_liabilities.insert(token_address, liability.checked_sub(redeem_amount).expect("Could not remove redeemed tokens from claim token liabilities."));
+                let mut token_vault = self.claim_vaults.get_mut(&token_address).expect("Could not find claim vault for redeemed tokens.");
+                assert!(token_vault.amount() >= redeem_amount, "Not enough tokens in component to redeem claim tokens.");
+                return_buckets.push(token_vault.take(redeem_amount));
+            }
+            return_buckets
+        }
+
+        // Creates a raffle and deposits the prizes into the claim vault. The seed hash commits to the secret seed that is revealed when drawing the raffle.
+        // If the raffle is not drawn before the reveal deadline, anyone can cancel it and the prizes are shared by the tickets.
+        // A raffle without tickets is refunded to the treasurer instead.
+        // Returns the raffle id and the tokens not needed for the prizes.
+        pub fn create_raffle(
+            &mut self,
+            reward_name: String,
+            prizes: Vec<Decimal>,
+            seed_hash: Hash,
+            entry_deadline_epoch: u64,
+            reveal_deadline_epoch: u64,
+            mut prize_bucket: Bucket,
+        ) -> (u64, Bucket) {
+            assert!(self.active,"Component has been deactivated.");
+            self.assert_not_campaign_reward_name(&reward_name);
+            assert!(prizes.len() > 0, "A raffle needs at least one prize.");
+            assert!(entry_deadline_epoch >= Runtime::current_epoch().number(), "Entry deadline epoch {:?} has already passed.", entry_deadline_epoch);
+            assert!(reveal_deadline_epoch > entry_deadline_epoch, "Reveal deadline epoch must be after the entry deadline epoch.");
+            let mut prizes_total = Decimal::ZERO;
+            for prize in prizes.iter() {
+                assert!(*prize > Decimal::ZERO, "Raffle prizes must be larger than 0.");
+                prizes_total = prizes_total.checked_add(*prize).expect("Could not add raffle prize to prizes total.");
+            }
+            if prizes_total > prize_bucket.amount() {
+                panic!("Not enough tokens sent in prize bucket. Needed {:?}, but found only {:?}.", prizes_total, prize_bucket.amount());
+            }
+            let reward_token = prize_bucket.resource_address();
+            self.assert_fungible_reward_token(&reward_token);
+            self.deposit_to_claim_vault(prize_bucket.take(prizes_total));
+            self.raffle_counter += 1;
+            let raffle_id = self.raffle_counter;
+            self.raffles.insert(raffle_id, RaffleData {
+                reward_name,
+                reward_token,
+                prizes,
+                seed_hash,
+                entry_deadline_epoch,
+                reveal_deadline_epoch,
+                tickets: vec![],
+                entropy: None,
+                drawn: false,
+                cancelled: false,
+            });
+            (raffle_id, prize_bucket)
+        }
+
+        pub fn add_raffle_tickets(&mut self, raffle_id: u64, tickets: Vec<(RaffleTicket, u64)>) {
+            let mut raffle = self.raffles.get_mut(&raffle_id).expect(&format!("Could not find raffle {:?}.", raffle_id));
+            assert!(Runtime::current_epoch().number() <= raffle.entry_deadline_epoch, "Entry deadline for raffle {:?} has passed.", raffle_id);
+            assert!(raffle.entropy.is_none(), "Raffle {:?} has already been closed.", raffle_id);
+            for (ticket, weight) in tickets {
+                assert!(weight > 0, "Raffle ticket weights must be larger than 0.");
+                raffle.tickets.push((ticket, weight));
+            }
+        }
+
+        // Closes the ticket registration of the raffle after the entry deadline.
+        // The entropy only depends on the committed seed hash, the final tickets and the entry deadline, so the closing transaction can not influence the winners.
+        pub fn close_raffle(&mut self, raffle_id: u64) {
+            let mut raffle = self.raffles.get_mut(&raffle_id).expect(&format!("Could not find raffle {:?}.", raffle_id));
+            assert!(Runtime::current_epoch().number() > raffle.entry_deadline_epoch, "Raffle {:?} can only be closed after the entry deadline.", raffle_id);
+            assert!(raffle.entropy.is_none(), "Raffle {:?} has already been closed.", raffle_id);
+            let mut entropy_bytes = raffle.seed_hash.0.to_vec();
+            entropy_bytes.extend(scrypto_encode(&raffle.tickets).expect("Could not encode raffle tickets."));
+            entropy_bytes.extend(raffle.entry_deadline_epoch.to_le_bytes());
+            raffle.entropy = Some(hash(entropy_bytes));
+        }
+
+        // Draws the closed raffle with the revealed seed. Winners are derived from the seed and the entropy, weighted by their tickets, and can only win one prize.
+        // Winning accounts and orders are credited like normal rewards. Returns the prizes that could not be assigned.
+        pub fn draw_raffle(&mut self, raffle_id: u64, seed: String) -> Bucket {
+            assert!(self.active,"Component has been deactivated.");
+            let raffle = self.raffles.get(&raffle_id).expect(&format!("Could not find raffle {:?}.", raffle_id)).clone();
+            assert!(!raffle.drawn, "Raffle {:?} has already been drawn.", raffle_id);
+            assert!(!raffle.cancelled, "Raffle {:?} has been cancelled.", raffle_id);
+            let entropy = raffle.entropy.expect(&format!("Raffle {:?} must be closed before it can be drawn.", raffle_id));
+            assert!(Runtime::current_epoch().number() <= raffle.reveal_deadline_epoch, "Reveal deadline for raffle {:?} has passed.", raffle_id);
+            assert!(hash(seed.as_bytes()) == raffle.seed_hash, "Revealed seed does not match the committed seed hash.");
+            let mut tickets = raffle.tickets.clone();
+            let mut winners: Vec<(RaffleTicket, Decimal)> = vec![];
+            let mut unassigned_prizes = Decimal::ZERO;
+            for (prize_index, prize) in raffle.prizes.iter().enumerate() {
+                let total_weight: u64 = tickets.iter().map(|(_ticket, weight)| *weight).sum();
+                if total_weight == 0 {
+                    unassigned_prizes = unassigned_prizes.checked_add(*prize).expect("Could not add raffle prize to unassigned prizes.");
+                    continue;
+                }
+                let prize_hash = hash(format!("{}:{}:{}", seed, entropy, prize_index).as_bytes());
+                let mut winning_number = u64::from_le_bytes(prize_hash.0[0..8].try_into().unwrap()) % total_weight;
+                let mut winner_index = 0;
+                for (ticket_index, (_ticket, weight)) in tickets.iter().enumerate() {
+                    if winning_number < *weight {
+                        winner_index = ticket_index;
+                        break;
+                    }
+                    winning_number -= *weight;
+                }
+                let (winner, _weight) = tickets.remove(winner_index);
+                match &winner {
+                    RaffleTicket::Account(account_address) => {
+                        self.load_account_rewards(raffle.reward_name.clone(), raffle.reward_token, vec![(account_address.clone(), *prize)], true);
+                    }
+                    RaffleTicket::Order(order_receipt) => {
+                        let order_id_string = self.create_order_index_string(&order_receipt.resource_address(), order_receipt.local_id());
+                        self.load_order_rewards(&raffle.reward_name, &raffle.reward_token, order_id_string, *prize, true);
+                    }
+                }
+                winners.push((winner, *prize));
+            }
+            self.raffles.get_mut(&raffle_id).unwrap().drawn = true;
+            Runtime::emit_event(RaffleDrawnEvent { raffle_id, seed, entropy, winners });
+            let mut return_bucket = Bucket::new(raffle.reward_token);
+            if unassigned_prizes > Decimal::ZERO {
+                return_bucket.put(self.claim_vaults.get_mut(&raffle.reward_token).unwrap().take(unassigned_prizes));
+            }
+            return_bucket
+        }
+
+        // Cancels a raffle with tickets that was not drawn before the reveal deadline. Can be called by anyone.
+        // The prizes are shared by all tickets, weighted by their tickets, and credited like normal rewards.
+        // The last ticket receives the rounding remainder, so the full prizes are shared.
+        pub fn cancel_raffle(&mut self, raffle_id: u64) {
+            let raffle = self.assert_cancellable_raffle(raffle_id);
+            let total_weight: u64 = raffle.tickets.iter().map(|(_ticket, weight)| *weight).sum();
+            assert!(total_weight > 0, "Raffle {:?} without tickets can only be refunded by the treasurer.", raffle_id);
+            let prizes_total = self.get_raffle_prizes_total(&raffle);
+            let ticket_count = raffle.tickets.len();
+            let mut refunded_total = Decimal::ZERO;
+            let mut refunds: Vec<(RaffleTicket, Decimal)> = vec![];
+            for (ticket_index, (ticket, weight)) in raffle.tickets.into_iter().enumerate() {
+                let refund = if ticket_index == ticket_count - 1 {
+                    prizes_total.checked_sub(refunded_total).expect("Could not calculate raffle refund.")
+                } else {
+                    prizes_total.checked_mul(Decimal::from(weight)).and_then(|amount| amount.checked_div(Decimal::from(total_weight))).expect("Could not calculate raffle refund.")
+                };
+                refunded_total = refunded_total.checked_add(refund).expect("Could not add raffle refund to refunded total.");
+                match &ticket {
+                    RaffleTicket::Account(account_address) => {
+                        self.load_account_rewards(raffle.reward_name.clone(), raffle.reward_token, vec![(account_address.clone(), refund)], true);
+                    }
+                    RaffleTicket::Order(order_receipt) => {
+                        let order_id_string = self.create_order_index_string(&order_receipt.resource_address(), order_receipt.local_id());
+                        self.load_order_rewards(&raffle.reward_name, &raffle.reward_token, order_id_string, refund, true);
+                    }
+                }
+                refunds.push((ticket, refund));
+            }
+            self.raffles.get_mut(&raffle_id).unwrap().cancelled = true;
+            Runtime::emit_event(RaffleCancelledEvent { raffle_id, refunds });
+        }
+
+        // Cancels a raffle without tickets that was not drawn before the reveal deadline and returns its prizes.
+        pub fn refund_raffle(&mut self, raffle_id: u64) -> Bucket {
+            let raffle = self.assert_cancellable_raffle(raffle_id);
+            assert!(raffle.tickets.is_empty(), "Raffle {:?} with tickets must be cancelled, so the prizes are shared by the tickets.", raffle_id);
+            let prizes_total = self.get_raffle_prizes_total(&raffle);
+            self.raffles.get_mut(&raffle_id).unwrap().cancelled = true;
+            Runtime::emit_event(RaffleCancelledEvent { raffle_id, refunds: vec![] });
+            self.claim_vaults.get_mut(&raffle.reward_token).unwrap().take(prizes_total)
+        }
+
+        pub fn get_raffle(&self, raffle_id: u64) -> Option<RaffleData> {
+            self.raffles.get(&raffle_id).map(|raffle| raffle.clone())
+        }
+
+        // Creates a faucet funded with all tokens in the bucket. Every account passing the access rule can claim the amount per claim once,
+        // until the faucet runs out. Returns the faucet id.
+        pub fn create_faucet(&mut self, access_rule: AccessRule, amount_per_claim: Decimal, faucet_bucket: Bucket) -> u64 {
+            assert!(self.active,"Component has been deactivated.");
+            assert!(amount_per_claim > Decimal::ZERO, "Amount per claim must be larger than 0.");
+            let reward_token = faucet_bucket.resource_address();
+            self.assert_fungible_reward_token(&reward_token);
+            let remaining = faucet_bucket.amount();
+            self.deposit_to_claim_vault(faucet_bucket);
+            self.faucet_counter += 1;
+            self.faucets.insert(self.faucet_counter, FaucetData {
+                reward_token,
+                amount_per_claim,
+                remaining,
+                access_rule,
+            });
+            self.faucet_counter
+        }
+
+        // Closes the faucet and returns the tokens left in it.
+        pub fn close_faucet(&mut self, faucet_id: u64) -> Bucket {
+            let faucet = self.faucets.remove(&faucet_id).expect(&format!("Could not find faucet {:?}.", faucet_id));
+            let mut return_bucket = Bucket::new(faucet.reward_token);
+            if faucet.remaining > Decimal::ZERO {
+                return_bucket.put(self.claim_vaults.get_mut(&faucet.reward_token).unwrap().take(faucet.remaining));
+            }
+            return_bucket
+        }
+
+        pub fn claim_faucet(&mut self, faucet_id: u64, account_address: ComponentAddress) -> Bucket {
+            assert!(self.active,"Component has been deactivated.");
+            Runtime::assert_access_rule(self.create_account_owner_rule(&account_address));
+            let mut faucet = self.faucets.get(&faucet_id).expect(&format!("Could not find faucet {:?}.", faucet_id)).clone();
+            Runtime::assert_access_rule(faucet.access_rule.clone());
+            let account_id = NonFungibleLocalId::string(self.create_account_id(&account_address)).expect(&format!("Could not convert {:?} into a valid NFT ID", account_address));
+            assert!(self.faucet_claims.get(&(faucet_id, account_id.clone())).is_none(), "Account {:?} has already claimed from faucet {:?}.", account_address, faucet_id);
+            assert!(faucet.remaining >= faucet.amount_per_claim, "Faucet {:?} has run out of tokens.", faucet_id);
+            faucet.remaining = faucet.remaining - faucet.amount_per_claim;
+            self.faucet_claims.insert((faucet_id, account_id), true);
+            let claimed_tokens = self.claim_vaults.get_mut(&faucet.reward_token).unwrap().take(faucet.amount_per_claim);
+            self.faucets.insert(faucet_id, faucet);
+            claimed_tokens
+        }
+
+        pub fn get_faucet(&self, faucet_id: u64) -> Option<FaucetData> {
+            self.faucets.get(&faucet_id).map(|faucet| faucet.clone())
+        }
+
+        // Allows the pair component to record fills for the orders of the order receipt. Fills accrue order rewards according to the rate table.
+        // Registering an already registered order receipt updates its config and keeps its remaining budget.
+        pub fn register_pair(
+            &mut self,
+            order_receipt_address: ResourceAddress,
+            pair_component: ComponentAddress,
+            reward_name: String,
+            reward_token: ResourceAddress,
+            rate_table: Vec<(Decimal, Decimal)>,
+        ) {
+            self.assert_not_campaign_reward_name(&reward_name);
+            self.assert_fungible_reward_token(&reward_token);
+            assert!(rate_table.len() > 0, "Rate table can not be empty.");
+            let mut previous_volume: Option<Decimal> = None;
+            for (minimum_volume, rate) in rate_table.iter() {
+                assert!(*rate >= Decimal::ZERO, "Rates can not be negative.");
+                if let Some(previous_volume) = previous_volume {
+                    assert!(*minimum_volume > previous_volume, "Rate table must be sorted by minimum fill volume.");
+                }
+                previous_volume = Some(*minimum_volume);
+            }
+            let mut remaining_budget = Decimal::ZERO;
+            if let Some(existing_config) = self.accrual_configs.get(&order_receipt_address) {
+                assert!(existing_config.reward_token == reward_token || existing_config.remaining_budget == Decimal::ZERO, "Reward token can not be changed while the pair has a remaining budget.");
+                remaining_budget = existing_config.remaining_budget;
+            } else {
+                self.accrual_order_receipts.push(order_receipt_address);
+            }
+            self.accrual_configs.insert(order_receipt_address, AccrualConfig {
+                pair_component,
+                reward_name,
+                reward_token,
+                rate_table,
+                remaining_budget,
+            });
+        }
+
+        // Removes the accrual config of the order receipt and returns its remaining budget. Rewards already accrued stay claimable.
+        pub fn unregister_pair(&mut self, order_receipt_address: ResourceAddress) -> Bucket {
+            let config = self.accrual_configs.remove(&order_receipt_address).expect(&format!("Could not find accrual config for order receipt {:?}.", order_receipt_address));
+            self.accrual_order_receipts.retain(|registered_address| *registered_address != order_receipt_address);
+            let mut return_bucket = Bucket::new(config.reward_token);
+            if config.remaining_budget > Decimal::ZERO {
+                return_bucket.put(self.claim_vaults.get_mut(&config.reward_token).unwrap().take(config.remaining_budget));
+            }
+            return_bucket
+        }
+
+        pub fn fund_accrual(&mut self, order_receipt_address: ResourceAddress, rewards_bucket: Bucket) {
+            let mut config = self.accrual_configs.get(&order_receipt_address).expect(&format!("Could not find accrual config for order receipt {:?}.", order_receipt_address)).clone();
+            assert!(config.reward_token == rewards_bucket.resource_address(), "Reward Token address must match tokens in Rewards Bucket.");
+            self.assert_fungible_reward_token(&config.reward_token);
+            config.remaining_budget = config.remaining_budget.checked_add(rewards_bucket.amount()).expect("Could not add rewards to remaining budget.");
+            self.deposit_to_claim_vault(rewards_bucket);
+            self.accrual_configs.insert(order_receipt_address, config);
+        }
+
+        // Called by a registered pair component when an order is filled. Returns the accrued rewards.
+        // Fills never fail because of rewards: nothing is accrued when the order receipt is not registered, the component is deactivated or the budget has run out.
+        pub fn record_fill(&mut self, order_id: NonFungibleGlobalId, volume: Decimal) -> Decimal {
+            let order_receipt_address = order_id.resource_address();
+            let mut config = match self.accrual_configs.get(&order_receipt_address) {
+                Some(config) => config.clone(),
+                None => return Decimal::ZERO,
+            };
+            Runtime::assert_access_rule(rule!(require(global_caller(config.pair_component))));
+            if !self.active || volume <= Decimal::ZERO {
+                return Decimal::ZERO;
+            }
+            let mut rate = Decimal::ZERO;
+            for (minimum_volume, volume_rate) in config.rate_table.iter() {
+                if volume >= *minimum_volume {
+                    rate = *volume_rate;
+                }
+            }
+            let mut accrued_reward = volume.checked_mul(rate).expect("Could not calculate accrued reward.");
+            if accrued_reward > config.remaining_budget {
+                accrued_reward = config.remaining_budget;
+            }
+            if accrued_reward > Decimal::ZERO {
+                let order_id_string = self.create_order_index_string(&order_receipt_address, order_id.local_id());
+                self.load_order_rewards(&config.reward_name, &config.reward_token, order_id_string, accrued_reward, true);
+                config.remaining_budget = config.remaining_budget - accrued_reward;
+                self.accrual_configs.insert(order_receipt_address, config);
+            }
+            accrued_reward
+        }
+
+        pub fn get_accrual_config(&self, order_receipt_address: ResourceAddress) -> Option<AccrualConfig> {
+            self.accrual_configs.get(&order_receipt_address).map(|config| config.clone())
+        }
+
+        pub fn get_claim_token_liability(&self, token_address: ResourceAddress) -> Decimal {
+            self.claim_token_liabilities.get(&token_address).map(|liability| liability.clone()).unwrap_or(Decimal::ZERO)
+        }
+
+        // Claims rewards for at most max_orders orders per call, so that accounts with many orders stay within the transaction cost limit.
+        // The order proofs hold one page of order receipts: at most max_orders ids, created from the ids that follow the cursor in order of
+        // receipt address and order id. Account NFT rewards are only claimed in the first call (without a cursor).
+        // A full page returns a cursor to the last order in the page, which must be passed with the next page. A page that is not full is the last page.
+        pub fn claim_rewards_paged(
+            &mut self,
+            reward_nft_proofs: Vec<NonFungibleProof>,
+            orders_proofs: Vec<NonFungibleProof>,
+            max_orders: u32,
+            cursor: Option<ClaimCursor>,
+        ) -> (Vec<Bucket>, Option<ClaimCursor>) {
+            assert!(self.active,"Component has been deactivated.");
+            assert!(max_orders > 0, "Max orders must be larger than 0.");
+            let mut token_totals: HashMap<ResourceAddress, Decimal> = HashMap::new();
+            let mut claimed_totals: HashMap<ResourceAddress, (Decimal, Decimal)> = HashMap::new();
+            let mut return_buckets: Vec<Bucket> = vec![];
+            if cursor.is_none() {
+                let (account_ids, refused_buckets) = self.collect_account_claims(reward_nft_proofs, &mut token_totals, true, &mut claimed_totals);
+                if let Some(account_id) = account_ids.first() {
+                    self.lock_fee_subsidy(account_id);
+                }
+                return_buckets = refused_buckets;
+            }
+            let mut page_orders: Vec<(ResourceAddress, NonFungibleLocalId)> = vec![];
+            for orders_proof in orders_proofs {
+                let proof_resource_address = orders_proof.resource_address();
+                for order_id in orders_proof.skip_checking().non_fungible_local_ids() {
+                    page_orders.push((proof_resource_address, order_id));
+                    assert!(page_orders.len() as u32 <= max_orders, "Order proofs hold more than max orders ({:?}) ids. Only submit the orders of one page.", max_orders);
+                }
+            }
+            page_orders.sort();
+            if let (Some(previous_cursor), Some((first_receipt_address, first_order_id))) = (&cursor, page_orders.first()) {
+                assert!((first_receipt_address, first_order_id) > (&previous_cursor.order_receipt_address, &previous_cursor.order_id), "Orders in the page must come after the cursor.");
+            }
+            let page_is_full = page_orders.len() as u32 == max_orders;
+            let mut next_cursor: Option<ClaimCursor> = None;
+            for (order_receipt_address, order_id) in page_orders {
+                let order_index_string = self.create_order_index_string(&order_receipt_address, &order_id);
+                self.take_order_rewards(&order_index_string, &mut token_totals);
+                if page_is_full {
+                    next_cursor = Some(ClaimCursor {
+                        order_receipt_address,
+                        order_id,
+                    });
+                }
+            }
+            return_buckets.extend(self.pay_out_rewards(token_totals, &mut claimed_totals));
+            (return_buckets, next_cursor)
+        }
+
+        // Sets the accounts that claimed rewards of the NFTs are deposited to. An empty list returns the rewards to the claimer again.
+        pub fn set_payout_splits(&mut self, reward_nft_proof: NonFungibleProof, payout_splits: Vec<(ComponentAddress, Decimal)>) {
+            assert!(reward_nft_proof.resource_address() == self.account_rewards_nft_manager.address(), "Wrong NFT submitted. Only Dexter Claim NFTs can be submitted.");
+            let mut new_payout_splits: Vec<PayoutSplit> = vec![];
+            let mut total_percentage = Decimal::ZERO;
+            for (account_address, percentage) in payout_splits {
+                assert!(percentage > Decimal::ZERO, "Payout split percentages must be larger than 0.");
+                total_percentage = total_percentage + percentage;
+                new_payout_splits.push(PayoutSplit { account_address, percentage });
+            }
+            assert!(new_payout_splits.len() == 0 || total_percentage == dec!(100), "Payout split percentages must add up to 100, but found {:?}.", total_percentage);
+            for nft_id in reward_nft_proof.skip_checking().non_fungible_local_ids() {
+                if self.strict_claims {
+                    self.assert_account_owner(&nft_id);
+                }
+                let mut account_data = self.load_account_data(&nft_id);
+                account_data.payout_splits = new_payout_splits.clone();
+                self.save_account_data(&nft_id, account_data);
+            }
+        }
+
+        // Allows the delegate to claim the rewards of the NFTs until the expiry epoch. Claimed rewards still go to the NFT accounts or their payout splits.
+        pub fn grant_claim_delegate(&mut self, reward_nft_proof: NonFungibleProof, delegate: ClaimDelegate, expires_at_epoch: u64) {
+            assert!(reward_nft_proof.resource_address() == self.account_rewards_nft_manager.address(), "Wrong NFT submitted. Only Dexter Claim NFTs can be submitted.");
+            assert!(expires_at_epoch >= Runtime::current_epoch().number(), "Expiry epoch {:?} has already passed.", expires_at_epoch);
+            for nft_id in reward_nft_proof.skip_checking().non_fungible_local_ids() {
+                // the beneficiary account must be known to pay out delegated claims
+                self.assert_account_owner(&nft_id);
+                self.claim_delegates.insert(nft_id, DelegateGrant { delegate: delegate.clone(), expires_at_epoch });
+            }
+        }
+
+        pub fn revoke_claim_delegate(&mut self, reward_nft_proof: NonFungibleProof) {
+            assert!(reward_nft_proof.resource_address() == self.account_rewards_nft_manager.address(), "Wrong NFT submitted. Only Dexter Claim NFTs can be submitted.");
+            for nft_id in reward_nft_proof.skip_checking().non_fungible_local_ids() {
+                if self.strict_claims {
+                    self.assert_account_owner(&nft_id);
+                }
+                self.claim_delegates.remove(&nft_id);
+            }
+        }
+
+        // Claims the rewards of the account NFTs as their delegate. Rewards are deposited to the payout splits of the NFT or to the NFT account.
+        pub fn delegated_claim(&mut self, account_ids: Vec<NonFungibleLocalId>) {
+            assert!(self.active,"Component has been deactivated.");
+            let mut claimed_totals: HashMap<ResourceAddress, (Decimal, Decimal)> = HashMap::new();
+            for account_id in account_ids {
+                let delegate_grant = self.claim_delegates.get(&account_id).expect(&format!("No claim delegate found for NFT {:?}.", account_id)).clone();
+                assert!(Runtime::current_epoch().number() <= delegate_grant.expires_at_epoch, "Claim delegation for NFT {:?} has expired.", account_id);
+                match delegate_grant.delegate {
+                    ClaimDelegate::Badge(badge) => Runtime::assert_access_rule(rule!(require(badge))),
+                    ClaimDelegate::Account(delegate_account) => Runtime::assert_access_rule(self.create_account_owner_rule(&delegate_account)),
+                }
+                let account_address = self.get_account_address(&account_id);
+                let mut token_totals: HashMap<ResourceAddress, Decimal> = HashMap::new();
+                let payout_splits = self.take_account_rewards(&account_id, &mut token_totals);
+                let mut claimed_buckets = self.pay_out_rewards(token_totals, &mut claimed_totals);
+                claimed_buckets.extend(self.take_account_nft_rewards(&account_id));
+                if payout_splits.len() > 0 {
+                    claimed_buckets = self.distribute_payout(claimed_buckets, payout_splits);
+                }
+                if claimed_buckets.len() > 0 {
+                    let account_component: Global<AnyComponent> = Global::from(account_address);
+                    account_component.call::<(Vec<Bucket>,Option<ResourceOrNonFungible>),()>("try_deposit_batch_or_abort", &(claimed_buckets, None));
+                }
+            }
+        }
+
+        pub fn claim_for_account(&mut self, account_address: ComponentAddress) {
+            assert!(self.active,"Component has been deactivated.");
+            Runtime::assert_access_rule(self.create_account_owner_rule(&account_address));
+            let account_id = NonFungibleLocalId::string(self.create_account_id(&account_address)).expect(&format!("Could not convert {:?} into a valid NFT ID", account_address));
+            let mut token_totals: HashMap<ResourceAddress, Decimal> = HashMap::new();
+            let mut payout_splits: Vec<PayoutSplit> = vec![];
+            if self.account_rewards_nft_manager.non_fungible_exists(&account_id) {
+                payout_splits = self.take_account_rewards(&account_id, &mut token_totals);
+            }
+            if self.frozen_accounts.get(&account_id).is_none() {
+                if let Some(pending_rewards) = self.pending_rewards.remove(&account_address) {
+                    self.add_to_token_totals(pending_rewards, &mut token_totals);
+                }
+            }
+            let mut claimed_buckets = self.pay_out_rewards(token_totals, &mut HashMap::new());
+            claimed_buckets.extend(self.take_account_nft_rewards(&account_id));
+            if claimed_buckets.iter().any(|claimed_bucket| !claimed_bucket.is_empty()) {
+                self.lock_fee_subsidy(&account_id);
+            }
+            if payout_splits.len() > 0 {
+                claimed_buckets = self.distribute_payout(claimed_buckets, payout_splits);
+            }
+            if claimed_buckets.len() > 0 {
+                let account_component: Global<AnyComponent> = Global::from(account_address);
+                account_component.call::<(Vec<Bucket>,Option<ResourceOrNonFungible>),()>("try_deposit_batch_or_abort", &(claimed_buckets, None));
+            }
+        }
+
+        pub fn deliver_pending_rewards(&mut self, account_address: ComponentAddress) {
+            assert!(self.active,"Component has been deactivated.");
+            let pending_rewards = self.pending_rewards.remove(&account_address).expect(&format!("No pending rewards found for account {:?}.", account_address));
+            let account_id = NonFungibleLocalId::string(self.create_account_id(&account_address)).expect(&format!("Could not convert {:?} into a valid NFT ID", account_address));
+            if !self.account_rewards_nft_manager.non_fungible_exists(&account_id) {
+                assert!(self.mint_account_nft(&account_address, &account_id), "Account {:?} still refuses the rewards NFT deposit.", account_address);
+            }
+            let mut account_data: AccountRewardsData = self.account_rewards_nft_manager.get_non_fungible_data(&account_id);
+            for (reward_name, name_rewards) in pending_rewards {
+                for (reward_token, token_reward) in name_rewards {
+                    self.update_rewards(&mut account_data.rewards, &reward_name, &reward_token, token_reward, true);
+                }
+            }
+            self.update_account_nft_rewards(&account_id, account_data.rewards);
+        }
+
+        // Moves all claim vaults, claim tokens and the account rewards NFTs to the successor component and deactivates this component.
+        // The successor must allow this component to call its accept_migrated methods, and must not have minted account rewards NFTs itself.
+        // The successor takes over the nft authority badge, so it can keep minting, burning and updating the existing account rewards NFTs.
+        // Faucets, raffles that are not drawn or cancelled and accrual configs hold tokens in the claim vaults, so they are moved as well.
+        // The successor gives the faucets and raffles new ids.
+        pub fn migrate_to(&mut self, successor: ComponentAddress) {
+            assert!(self.migrated_to.is_none(), "Component has already been migrated.");
+            self.active = false;
+            self.migrated_to = Some(successor);
+            let mut claim_buckets: Vec<Bucket> = vec![];
+            for token_address in self.claim_tokens.iter() {
+                let mut token_vault = self.claim_vaults.get_mut(token_address).unwrap();
+                claim_buckets.push(token_vault.take_all());
+            }
+            let mut claim_tokens: Vec<(ResourceAddress, ResourceAddress, Decimal)> = vec![];
+            for token_address in self.claim_tokens.iter() {
+                if let Some(claim_token_manager) = self.claim_token_managers.remove(token_address) {
+                    let liability = self.claim_token_liabilities.remove(token_address).unwrap_or(Decimal::ZERO);
+                    claim_tokens.push((token_address.clone(), claim_token_manager.address(), liability));
+                }
+            }
+            let mut faucets: Vec<FaucetData> = vec![];
+            let mut faucet_ids: Vec<u64> = vec![];
+            for faucet_id in 1..=self.faucet_counter {
+                if let Some(faucet) = self.faucets.remove(&faucet_id) {
+                    faucets.push(faucet);
+                    faucet_ids.push(faucet_id);
+                }
+            }
+            let mut raffles: Vec<RaffleData> = vec![];
+            for raffle_id in 1..=self.raffle_counter {
+                let is_open = self.raffles.get(&raffle_id).map(|raffle| !raffle.drawn && !raffle.cancelled).unwrap_or(false);
+                if is_open {
+                    raffles.push(self.raffles.remove(&raffle_id).unwrap());
+                }
+            }
+            let mut accrual_configs: Vec<(ResourceAddress, AccrualConfig)> = vec![];
+            for order_receipt_address in self.accrual_order_receipts.drain(..) {
+                if let Some(config) = self.accrual_configs.remove(&order_receipt_address) {
+                    accrual_configs.push((order_receipt_address, config));
+                }
+            }
+            let nft_authority_badge = match &mut self.predecessor_nft_authority_badge {
+                Some(predecessor_badge) => predecessor_badge.take_all(),
+                None => self.nft_authority_badge.take_all(),
+            };
+            // the nft manager is handed over first, so the successor can check that it did not create claim tokens itself
+            let successor_component: Global<AnyComponent> = Global::from(successor);
+            successor_component.call::<(ResourceAddress, Bucket),()>("accept_migrated_nft_manager", &(self.account_rewards_nft_manager.address(), nft_authority_badge));
+            successor_component.call::<(Vec<Bucket>,),()>("accept_migrated_vaults", &(claim_buckets,));
+            successor_component.call::<(Vec<(ResourceAddress, ResourceAddress, Decimal)>,),()>("accept_migrated_claim_tokens", &(claim_tokens,));
+            let successor_faucet_ids = successor_component.call::<(Vec<FaucetData>, Vec<RaffleData>, Vec<(ResourceAddress, AccrualConfig)>),Vec<u64>>("accept_migrated_allocations", &(faucets, raffles, accrual_configs));
+            self.migrated_faucet_ids = faucet_ids.into_iter().zip(successor_faucet_ids.into_iter()).collect();
+        }
+
+        // Moves order rewards and frozen orders to the successor component. Can be called in batches after migrate_to.
+        pub fn migrate_orders(&mut self, order_ids: Vec<String>) {
+            let successor = self.migrated_to.expect("Component must be migrated before its orders can be migrated.");
+            let mut orders_data: Vec<OrderRewardsData> = vec![];
+            let mut frozen_orders: Vec<(String, u64)> = vec![];
+            for order_id in order_ids {
+                if let Some(order_data) = self.order_rewards.remove(&order_id) {
+                    orders_data.push(order_data);
+                }
+                if let Some(frozen_at_epoch) = self.frozen_orders.remove(&order_id) {
+                    frozen_orders.push((order_id, frozen_at_epoch));
+                }
+            }
+            let successor_component: Global<AnyComponent> = Global::from(successor);
+            successor_component.call::<(Vec<OrderRewardsData>, Vec<(String, u64)>),()>("accept_migrated_orders", &(orders_data, frozen_orders));
+        }
+
+        // Moves the state that this component keeps per account to the successor component. Can be called in batches after migrate_to.
+        pub fn migrate_accounts(&mut self, account_addresses: Vec<ComponentAddress>) {
+            let successor = self.migrated_to.expect("Component must be migrated before its accounts can be migrated.");
+            let mut accounts_data: Vec<MigratedAccountData> = vec![];
+            for account_address in account_addresses {
+                let account_id = NonFungibleLocalId::string(self.create_account_id(&account_address)).expect(&format!("Could not convert {:?} into a valid NFT ID", account_address));
+                let mut faucet_claims: Vec<u64> = vec![];
+                for (faucet_id, successor_faucet_id) in self.migrated_faucet_ids.iter() {
+                    if self.faucet_claims.remove(&(*faucet_id, account_id.clone())).is_some() {
+                        faucet_claims.push(*successor_faucet_id);
+                    }
+                }
+                accounts_data.push(MigratedAccountData {
+                    account_address,
+                    pending_rewards: self.pending_rewards.remove(&account_address),
+                    account_data: self.account_data.remove(&account_id),
+                    nft_rewards: self.account_nft_rewards.remove(&account_id),
+                    claim_delegate: self.claim_delegates.remove(&account_id),
+                    frozen_at_epoch: self.frozen_accounts.remove(&account_id),
+                    faucet_claims,
+                });
+            }
+            let successor_component: Global<AnyComponent> = Global::from(successor);
+            successor_component.call::<(Vec<MigratedAccountData>,),()>("accept_migrated_accounts", &(accounts_data,));
+        }
+
+        pub fn accept_migrated_vaults(&mut self, claim_buckets: Vec<Bucket>) {
+            for claim_bucket in claim_buckets {
+                self.deposit_to_claim_vault(claim_bucket);
+            }
+        }
+
+        // Takes over the account rewards NFTs of the predecessor, replacing the unused NFT resource of this component.
+        pub fn accept_migrated_nft_manager(&mut self, nft_manager_address: ResourceAddress, nft_authority_badge: Bucket) {
+            assert!(self.predecessor_nft_authority_badge.is_none(), "Account rewards NFTs have already been migrated to this component.");
+            assert!(self.account_rewards_nft_manager.total_supply().unwrap_or(Decimal::ZERO) == Decimal::ZERO, "Account rewards NFTs have already been minted by this component.");
+            assert!(self.claim_tokens.iter().all(|token_address| self.claim_token_managers.get(token_address).is_none()), "Claim tokens have already been created by this component.");
+            self.account_rewards_nft_manager = ResourceManager::from(nft_manager_address);
+            self.predecessor_nft_authority_badge = Some(Vault::with_bucket(nft_authority_badge));
+        }
+
+        // Takes over the claim tokens of the predecessor, so they can be redeemed from the migrated claim vaults.
+        pub fn accept_migrated_claim_tokens(&mut self, claim_tokens: Vec<(ResourceAddress, ResourceAddress, Decimal)>) {
+            for (token_address, claim_token_address, liability) in claim_tokens {
+                self.claim_token_rewards.insert(claim_token_address, token_address);
+                if self.claim_token_managers.get(&token_address).is_none() {
+                    self.claim_token_managers.insert(token_address, ResourceManager::from(claim_token_address));
+                }
+                let existing_liability = self.claim_token_liabilities.get(&token_address).map(|liability| liability.clone()).unwrap_or(Decimal::ZERO);
+                self.claim_token_liabilities.insert(token_address, existing_liability.checked_add(liability).expect("Could not add migrated claim token liabilities."));
+            }
+        }
+
+        // Takes over the faucets, open raffles and accrual configs of the predecessor, whose tokens are in the migrated claim vaults.
+        // Returns the ids of the migrated faucets in this component, in the order they were received.
+        pub fn accept_migrated_allocations(&mut self, faucets: Vec<FaucetData>, raffles: Vec<RaffleData>, accrual_configs: Vec<(ResourceAddress, AccrualConfig)>) -> Vec<u64> {
+            let mut faucet_ids: Vec<u64> = vec![];
+            for faucet in faucets {
+                self.faucet_counter += 1;
+                self.faucets.insert(self.faucet_counter, faucet);
+                faucet_ids.push(self.faucet_counter);
+            }
+            for raffle in raffles {
+                self.raffle_counter += 1;
+                self.raffles.insert(self.raffle_counter, raffle);
+            }
+            for (order_receipt_address, config) in accrual_configs {
+                let existing_config = self.accrual_configs.get(&order_receipt_address).map(|existing_config| existing_config.clone());
+                match existing_config {
+                    Some(mut existing_config) => {
+                        assert!(existing_config.reward_token == config.reward_token, "Accrual config of order receipt {:?} uses a different reward token.", order_receipt_address);
+                        existing_config.remaining_budget = existing_config.remaining_budget.checked_add(config.remaining_budget).expect("Could not add migrated remaining budget.");
+                        self.accrual_configs.insert(order_receipt_address, existing_config);
+                    }
+                    None => {
+                        self.accrual_order_receipts.push(order_receipt_address);
+                        self.accrual_configs.insert(order_receipt_address, config);
+                    }
+                }
+            }
+            faucet_ids
+        }
+
+        pub fn accept_migrated_orders(&mut self, orders_data: Vec<OrderRewardsData>, frozen_orders: Vec<(String, u64)>) {
+            for (order_id, frozen_at_epoch) in frozen_orders {
+                self.frozen_orders.insert(order_id, frozen_at_epoch);
+            }
+            for order_data in orders_data {
+                let mut existing_order_data = self.order_rewards.get(&order_data.order_id).map(|existing_data| existing_data.clone()).unwrap_or(OrderRewardsData {
+                    order_id: order_data.order_id.clone(),
+                    rewards: HashMap::new(),
+                });
+                for (reward_name, name_rewards) in order_data.rewards {
+                    for (reward_token, token_reward) in name_rewards {
+                        self.update_rewards(&mut existing_order_data.rewards, &reward_name, &reward_token, token_reward, true);
+                    }
+                }
+                self.order_rewards.insert(order_data.order_id, existing_order_data);
+            }
+        }
+
+        pub fn accept_migrated_accounts(&mut self, accounts_data: Vec<MigratedAccountData>) {
+            for migrated_data in accounts_data {
+                let account_address = migrated_data.account_address;
+                let account_id = NonFungibleLocalId::string(self.create_account_id(&account_address)).expect(&format!("Could not convert {:?} into a valid NFT ID", account_address));
+                if let Some(migrated_rewards) = migrated_data.pending_rewards {
+                    let mut pending_rewards = self.pending_rewards.get(&account_address).map(|rewards| rewards.clone()).unwrap_or(HashMap::new());
+                    for (reward_name, name_rewards) in migrated_rewards {
+                        for (reward_token, token_reward) in name_rewards {
+                            self.update_rewards(&mut pending_rewards, &reward_name, &reward_token, token_reward, true);
+                        }
+                    }
+                    self.pending_rewards.insert(account_address, pending_rewards);
+                }
+                if let Some(migrated_account_data) = migrated_data.account_data {
+                    let migrated_account_data = migrated_account_data.upgrade();
+                    let mut account_data = self.load_account_data(&account_id);
+                    for (token_address, claimed) in migrated_account_data.total_claimed {
+                        let total_claimed = account_data.total_claimed.get(&token_address).cloned().unwrap_or(Decimal::ZERO);
+                        account_data.total_claimed.insert(token_address, total_claimed.checked_add(claimed).expect("Could not add claimed rewards to total claimed."));
+                    }
+                    account_data.last_claim_epoch = account_data.last_claim_epoch.max(migrated_account_data.last_claim_epoch);
+                    if account_data.payout_splits.len() == 0 {
+                        account_data.payout_splits = migrated_account_data.payout_splits;
+                    }
+                    self.save_account_data(&account_id, account_data);
+                }
+                if let Some(migrated_nft_rewards) = migrated_data.nft_rewards {
+                    let mut nft_rewards = self.account_nft_rewards.get(&account_id).map(|rewards| rewards.clone()).unwrap_or(HashMap::new());
+                    for (reward_name, name_rewards) in migrated_nft_rewards {
+                        for (reward_token, nft_ids) in name_rewards {
+                            nft_rewards.entry(reward_name.clone()).or_insert(HashMap::new()).entry(reward_token).or_insert(vec![]).extend(nft_ids);
+                        }
+                    }
+                    self.account_nft_rewards.insert(account_id.clone(), nft_rewards);
+                }
+                if let Some(claim_delegate) = migrated_data.claim_delegate {
+                    if self.claim_delegates.get(&account_id).is_none() {
+                        self.claim_delegates.insert(account_id.clone(), claim_delegate);
+                    }
+                }
+                for faucet_id in migrated_data.faucet_claims {
+                    self.faucet_claims.insert((faucet_id, account_id.clone()), true);
+                }
+                if let Some(frozen_at_epoch) = migrated_data.frozen_at_epoch {
+                    self.frozen_accounts.insert(account_id, frozen_at_epoch);
+                }
+            }
+        }
+
+        pub fn set_claim_fee(&mut self, token_address: ResourceAddress, basis_points: u16, minimum: Decimal) {
+            assert!(basis_points <= 10000, "Claim fee can not be more than 10000 basis points.");
+            assert!(minimum >= Decimal::ZERO, "Minimum claim fee can not be negative.");
+            self.claim_fees.insert(token_address, ClaimFee { basis_points, minimum });
+        }
+
+        pub fn remove_claim_fee(&mut self, token_address: ResourceAddress) {
+            self.claim_fees.remove(&token_address);
+        }
+
+        pub fn withdraw_fees(&mut self, token_address: ResourceAddress) -> Bucket {
+            let mut fee_vault = self.fee_vaults.get_mut(&token_address).expect(&format!("No fees found for token {:?}.", token_address));
+            fee_vault.take_all()
+        }
+
+        pub fn fund_fee_subsidy(&mut self, xrd_bucket: Bucket) {
+            self.fee_subsidy_vault.put(xrd_bucket);
+        }
+
+        pub fn withdraw_fee_subsidy(&mut self, amount: Decimal) -> Bucket {
+            self.fee_subsidy_vault.take(amount)
+        }
+
+        pub fn set_fee_subsidy_limits(&mut self, per_claim: Decimal, per_account_epoch: Decimal) {
+            assert!(per_claim >= Decimal::ZERO && per_account_epoch >= Decimal::ZERO, "Fee subsidy limits can not be negative.");
+            self.fee_subsidy_per_claim = per_claim;
+            self.fee_subsidy_per_account_epoch = per_account_epoch;
+        }
 
-            new_component
+        pub fn approve_validators(&mut self, validators: Vec<ComponentAddress>) {
+            for validator in validators {
+                self.approved_validators.insert(validator, true);
+            }
         }
 
-        pub fn add_account_rewards(
-            &mut self,
-            reward_name: String,
-            reward_token: ResourceAddress,
-            account_rewards: Vec<(ComponentAddress,Decimal)>,
-            rewards_bucket: Bucket,
-        ) -> Bucket {
-            self.add_rewards(reward_name, reward_token, account_rewards, String::from(""), rewards_bucket)
+        pub fn revoke_validators(&mut self, validators: Vec<ComponentAddress>) {
+            for validator in validators.iter() {
+                self.approved_validators.remove(validator);
+            }
         }
 
-         pub fn add_orders_rewards(
-            &mut self,
-            reward_name: String,
-            reward_token: ResourceAddress,
-            orders_rewards_string: String,
-            rewards_bucket: Bucket,
-        ) -> Bucket {
-            self.add_rewards(reward_name, reward_token, vec![], orders_rewards_string, rewards_bucket)
+        pub fn set_restrict_validators(&mut self, restrict_validators: bool) {
+            self.restrict_validators = restrict_validators;
         }
 
-        pub fn add_rewards(
+        pub fn add_forward_targets(&mut self, forward_targets: Vec<(ComponentAddress, String)>) {
+            for forward_target in forward_targets {
+                self.forward_targets.insert(forward_target, true);
+            }
+        }
+
+        pub fn remove_forward_targets(&mut self, forward_targets: Vec<(ComponentAddress, String)>) {
+            for forward_target in forward_targets.iter() {
+                self.forward_targets.remove(forward_target);
+            }
+        }
+
+        pub fn deactivate(&mut self) {
+            self.active = false;
+        }
+        
+        pub fn activate(&mut self) {
+            assert!(self.migrated_to.is_none(), "Component has been migrated and can not be activated again.");
+            self.active = true;
+        }
+
+        fn add_named_rewards(
             &mut self,
             reward_name: String,
             reward_token: ResourceAddress,
             account_rewards: Vec<(ComponentAddress,Decimal)>,
             orders_rewards_string: String,
             mut rewards_bucket: Bucket,
-        ) -> Bucket {
+        ) -> (Bucket, RewardsReport) {
             assert!(self.active,"Component has been deactivated.");
             assert!(reward_token == rewards_bucket.resource_address(), "Reward Token address must match tokens in Rewards Bucket.");
+            self.assert_fungible_reward_token(&reward_token);
             // comment below out for production
             // let _rewards_bucket_address_string =
             //     self.create_resource_address_string(
@@ -209,67 +1905,40 @@ mod dexter_claim_component {
             //     rewards_bucket.amount()
             // );
             // comment above out for production
-            let mut reward_tokens_total = Decimal::ZERO;
+            let mut entries: Vec<RewardEntryReport> = vec![];
             if account_rewards.len() > 0 {
-                reward_tokens_total = reward_tokens_total + self.load_account_rewards(reward_name.clone(), reward_token.clone(), account_rewards, true);
+                entries.extend(self.load_account_rewards(reward_name.clone(), reward_token.clone(), account_rewards, true));
             }
             if orders_rewards_string != "" {
                 let order_rewards = self.parse_orders_rewards_data(orders_rewards_string);
-                reward_tokens_total = reward_tokens_total + self.load_orders_rewards(reward_name.clone(), reward_token.clone(), order_rewards, true);
+                entries.extend(self.load_orders_rewards(reward_name.clone(), reward_token.clone(), order_rewards, true));
             }
+            let report = self.create_rewards_report(reward_name, reward_token, entries);
+            let reward_tokens_total = report.total_amount;
             if reward_tokens_total > rewards_bucket.amount() {
                 panic!("Not enough tokens sent in rewards bucket. Needed {:?}, but found only {:?}.", reward_tokens_total.clone(), rewards_bucket.amount());
             }
-            if self
-                .claim_vaults
-                .get(&rewards_bucket.resource_address())
-                .is_some()
-            {
-                let mut claim_vault = self
-                    .claim_vaults
-                    .get_mut(&rewards_bucket.resource_address())
-                    .unwrap();
-                claim_vault.put(rewards_bucket.take(reward_tokens_total));
-            } else {
-                let new_vault = Vault::with_bucket(rewards_bucket.take(reward_tokens_total));
-                self.claim_vaults
-                    .insert(rewards_bucket.resource_address(), new_vault);
-            }
-            rewards_bucket
+            self.deposit_to_claim_vault(rewards_bucket.take(reward_tokens_total));
+            (rewards_bucket, report)
         }
 
-        pub fn remove_account_rewards(
-            &mut self, 
-            reward_name: String,
-            reward_token: ResourceAddress,
-            account_rewards: Vec<(ComponentAddress,Decimal)>
-        ) -> Bucket {
-            self.remove_rewards(reward_name, reward_token, account_rewards, String::from(""))
-        }
-        
-        pub fn remove_orders_rewards(
-            &mut self, 
-            reward_token: ResourceAddress,
-            orders_rewards_string: String
-        ) -> Bucket {
-            self.remove_rewards(String::from("Market Order Rewards"), reward_token, vec![], orders_rewards_string)
-        }
-        
-        pub fn remove_rewards(
-            &mut self, 
+        fn remove_named_rewards(
+            &mut self,
             reward_name: String,
             reward_token: ResourceAddress,
             account_rewards: Vec<(ComponentAddress,Decimal)>,
             orders_rewards_string: String
-        ) -> Bucket {
-            let mut reward_tokens_removed = Decimal::ZERO;
+        ) -> (Bucket, RewardsReport) {
+            let mut entries: Vec<RewardEntryReport> = vec![];
             if account_rewards.len() > 0 {
-                reward_tokens_removed = reward_tokens_removed + self.load_account_rewards(reward_name.clone(), reward_token.clone(), account_rewards, false);
+                entries.extend(self.load_account_rewards(reward_name.clone(), reward_token.clone(), account_rewards, false));
             }
             if orders_rewards_string.len() > 0 {
                 let order_rewards = self.parse_orders_rewards_data(orders_rewards_string);
-                reward_tokens_removed = reward_tokens_removed + self.load_orders_rewards(reward_name.clone(), reward_token.clone(), order_rewards, false);
+                entries.extend(self.load_orders_rewards(reward_name.clone(), reward_token.clone(), order_rewards, false));
             }
+            let report = self.create_rewards_report(reward_name, reward_token, entries);
+            let reward_tokens_removed = report.total_amount;
             let mut return_bucket = Bucket::new(reward_token.clone());
             if reward_tokens_removed > Decimal::ZERO {
                 let mut token_vault = self.claim_vaults.get_mut(&reward_token).expect(&format!(
@@ -282,169 +1951,467 @@ mod dexter_claim_component {
                     panic!("Not enough tokens in claim vault for token {:?}. Required {:?}, but only found {:?}", reward_token.clone(), reward_tokens_removed.clone(), token_vault.amount());
                 }
             }
-            return_bucket
+            (return_bucket, report)
         }
 
-        pub fn claim_rewards(
+        fn add_campaign_rewards(
             &mut self,
-            reward_nft_proofs: Vec<NonFungibleProof>,
-            orders_proofs: Vec<NonFungibleProof>,
-        ) -> Vec<Bucket> {
-            // info!("Starting to claim rewards!");
-            assert!(self.active,"Component has been deactivated.");
-            let mut token_totals: HashMap<ResourceAddress, Decimal> = HashMap::new();
-            let mut return_buckets: Vec<Bucket> = vec![];
+            campaign_badge: NonFungibleProof,
+            account_rewards: Vec<(ComponentAddress,Decimal)>,
+            orders_rewards_string: String,
+            rewards_bucket: Bucket,
+        ) -> (Bucket, RewardsReport) {
+            let campaign = self.get_campaign_from_badge(campaign_badge);
+            let reward_name = self.create_campaign_reward_name(&campaign.campaign_id);
+            let (return_bucket, report) = self.add_named_rewards(reward_name, campaign.reward_token, account_rewards, orders_rewards_string, rewards_bucket);
+            let new_allocated = campaign.allocated.checked_add(report.total_amount).expect("Could not add new rewards to campaign allocated total.");
+            assert!(new_allocated <= campaign.budget, "Campaign budget exceeded. Budget {:?}, but rewards would total {:?}.", campaign.budget, new_allocated);
+            self.campaigns.get_mut(&campaign.campaign_id).unwrap().allocated = new_allocated;
+            (return_bucket, report)
+        }
+
+        fn remove_campaign_rewards(
+            &mut self,
+            campaign_badge: NonFungibleProof,
+            account_rewards: Vec<(ComponentAddress,Decimal)>,
+            orders_rewards_string: String,
+        ) -> (Bucket, RewardsReport) {
+            let campaign = self.get_campaign_from_badge(campaign_badge);
+            let reward_name = self.create_campaign_reward_name(&campaign.campaign_id);
+            let (return_bucket, report) = self.remove_named_rewards(reward_name, campaign.reward_token, account_rewards, orders_rewards_string);
+            // claimed rewards are not tracked per campaign, so allocated can be lower than what is removed here
+            let new_allocated = (campaign.allocated - report.total_amount).max(Decimal::ZERO);
+            self.campaigns.get_mut(&campaign.campaign_id).unwrap().allocated = new_allocated;
+            (return_bucket, report)
+        }
+
+        fn get_campaign_from_badge(&self, campaign_badge: NonFungibleProof) -> CampaignData {
+            assert!(campaign_badge.resource_address() == self.campaign_manager_badge_manager.address(), "Wrong badge submitted. Only campaign manager badges can be used to manage campaign rewards.");
+            let badge = campaign_badge.skip_checking().non_fungible::<CampaignManagerData>();
+            assert!(self.revoked_campaign_manager_badges.get(badge.local_id()).is_none(), "Campaign manager badge {:?} has been revoked.", badge.local_id());
+            let campaign_id = badge.data().campaign_id;
+            let campaign = self.campaigns.get(&campaign_id).expect(&format!("Campaign {:?} does not exist.", campaign_id)).clone();
+            campaign
+        }
+
+        // Rewards of a campaign are added under their own reward name, so they can only be changed through the campaign and count towards its budget.
+        fn create_campaign_reward_name(&self, campaign_id: &String) -> String {
+            format!("Campaign: {}", campaign_id)
+        }
+
+        fn assert_not_campaign_reward_name(&self, reward_name: &String) {
+            assert!(!reward_name.starts_with("Campaign: "), "Reward names starting with \"Campaign: \" are reserved for campaign rewards.");
+        }
+
+        // Collects the rewards of the account NFTs into token_totals. If use_payout_splits is true, rewards of NFTs with payout splits
+        // are paid out to the split accounts instead. The returned buckets contain the non-fungible rewards that were not paid out
+        // to split accounts and the portions refused by those accounts. The returned ids are the NFTs that had rewards to claim.
+        fn collect_account_claims(&mut self, reward_nft_proofs: Vec<NonFungibleProof>, token_totals: &mut HashMap<ResourceAddress, Decimal>, use_payout_splits: bool, claimed_totals: &mut HashMap<ResourceAddress, (Decimal, Decimal)>) -> (Vec<NonFungibleLocalId>, Vec<Bucket>) {
             let rewards_nft_address = self.account_rewards_nft_manager.address();
+            let mut account_ids: Vec<NonFungibleLocalId> = vec![];
+            let mut refused_buckets: Vec<Bucket> = vec![];
             for reward_proof in reward_nft_proofs {
                 assert!(reward_proof.resource_address() == rewards_nft_address.clone(), "Wrong NFT submitted. Only Dexter Claim NFTs can be submitted for claims.");
-                let nfts = reward_proof.skip_checking().non_fungibles::<AccountRewardsData>();
-                for nft in nfts {
-                    let nft_data = nft
-                        .data();
-                    // info!("Claim NFT Data: {:?}", nft_data);
-                    for reward_name_tokens in nft_data.rewards.into_values() {
-                        for (token_address_string, token_reward) in reward_name_tokens {
-                            let existing_token_total = token_totals.entry(token_address_string.clone()).or_insert(Decimal::ZERO).to_owned();
-                            token_totals.insert(
-                                token_address_string.clone(), 
-                                existing_token_total.checked_add(token_reward).expect(&format!("Could not add token reward {:?} to existing token total {:?}.", token_reward, existing_token_total))
-                            );
-                            // info!("Token totals: {:?}", token_totals);
+                let nft_ids = reward_proof.skip_checking().non_fungible_local_ids();
+                for nft_id in nft_ids {
+                    if self.strict_claims {
+                        self.assert_account_owner(&nft_id);
+                    }
+                    let mut account_token_totals: HashMap<ResourceAddress, Decimal> = HashMap::new();
+                    let payout_splits = self.take_account_rewards(&nft_id, &mut account_token_totals);
+                    let nft_buckets = self.take_account_nft_rewards(&nft_id);
+                    if account_token_totals.values().any(|token_reward| *token_reward > Decimal::ZERO) || nft_buckets.len() > 0 {
+                        account_ids.push(nft_id.clone());
+                    }
+                    if use_payout_splits && payout_splits.len() > 0 {
+                        let mut account_buckets = self.pay_out_rewards(account_token_totals, claimed_totals);
+                        account_buckets.extend(nft_buckets);
+                        refused_buckets.extend(self.distribute_payout(account_buckets, payout_splits));
+                    } else {
+                        refused_buckets.extend(nft_buckets);
+                        for (token_address, token_reward) in account_token_totals {
+                            let existing_token_total = token_totals.get(&token_address).cloned().unwrap_or(Decimal::ZERO);
+                            token_totals.insert(token_address, existing_token_total.checked_add(token_reward).expect(&format!("Could not add token reward {:?} to existing token total {:?}.", token_reward, existing_token_total)));
                         }
+                    }
+                }
+            }
+            (account_ids, refused_buckets)
+        }
+
+        // Deposits the buckets to the payout split accounts and returns the portions refused by those accounts.
+        // The last split receives what remains after rounding. Non-fungible rewards can not be split and go to the first split account.
+        fn distribute_payout(&self, payout_buckets: Vec<Bucket>, payout_splits: Vec<PayoutSplit>) -> Vec<Bucket> {
+            let mut refused_buckets: Vec<Bucket> = vec![];
+            let last_split_index = payout_splits.len() - 1;
+            for mut payout_bucket in payout_buckets {
+                let payout_amount = payout_bucket.amount();
+                if !payout_bucket.resource_address().is_fungible() {
+                    let account_component: Global<AnyComponent> = Global::from(payout_splits[0].account_address);
+                    let returned_bucket: Option<Bucket> = account_component.call::<(Bucket,Option<ResourceOrNonFungible>),_>("try_deposit_or_refund", &(payout_bucket, None));
+                    if let Some(returned_tokens) = returned_bucket {
+                        refused_buckets.push(returned_tokens);
+                    }
+                    continue;
+                }
+                for (split_index, payout_split) in payout_splits.iter().enumerate() {
+                    let split_bucket = if split_index == last_split_index {
+                        payout_bucket.take(payout_bucket.amount())
+                    } else {
+                        payout_bucket.take_advanced(payout_amount * payout_split.percentage / dec!(100), WithdrawStrategy::Rounded(RoundingMode::ToZero))
                     };
-                    self.account_rewards_nft_manager.update_non_fungible_data::<HashMap<String, HashMap<String, Decimal>>>(nft.local_id(), "rewards", HashMap::new());
+                    if split_bucket.amount() > Decimal::ZERO {
+                        let account_component: Global<AnyComponent> = Global::from(payout_split.account_address);
+                        let returned_bucket: Option<Bucket> = account_component.call::<(Bucket,Option<ResourceOrNonFungible>),_>("try_deposit_or_refund", &(split_bucket, None));
+                        if let Some(returned_tokens) = returned_bucket {
+                            refused_buckets.push(returned_tokens);
+                        }
+                    } else {
+                        split_bucket.drop_empty();
+                    }
                 }
+                payout_bucket.drop_empty();
             }
-            // info!("Handled accounts claims");
+            refused_buckets
+        }
 
-            // info!("Starting to handle order claims");
-            let mut orders_to_remove: Vec<String> = vec![];
+        // Pays the transaction fee of a claim from the fee subsidy vault, limited per claim and per account NFT per epoch.
+        // Only claims that pay out rewards of an account NFT are subsidised, so accounts without XRD can claim their rewards.
+        // Order only claims, delegated claims and claim token conversions are not subsidised.
+        fn lock_fee_subsidy(&mut self, account_id: &NonFungibleLocalId) {
+            let current_epoch = Runtime::current_epoch().number();
+            let mut epoch_usage = Decimal::ZERO;
+            if let Some((usage_epoch, usage_amount)) = self.fee_subsidy_usage.get(account_id).map(|usage| usage.clone()) {
+                if usage_epoch == current_epoch {
+                    epoch_usage = usage_amount;
+                }
+            }
+            let fee_amount = self.fee_subsidy_per_claim
+                .min(self.fee_subsidy_per_account_epoch - epoch_usage)
+                .min(self.fee_subsidy_vault.amount());
+            if fee_amount > Decimal::ZERO {
+                self.fee_subsidy_vault.as_fungible().lock_fee(fee_amount);
+                self.fee_subsidy_usage.insert(account_id.clone(), (current_epoch, epoch_usage + fee_amount));
+            }
+        }
+
+        fn collect_order_claims(&mut self, orders_proofs: Vec<NonFungibleProof>, token_totals: &mut HashMap<ResourceAddress, Decimal>) {
             for orders_proof in orders_proofs {
                 let proof_resource_address = orders_proof.resource_address();
-                let resource_string = self.create_resource_address_string(
-                            &proof_resource_address,
-                        );
                 let order_ids = orders_proof.skip_checking().non_fungible_local_ids();
                 for order_id in order_ids {
-                    let mut order_index_string =
-                        resource_string.clone();
-                    let order_id_string = order_id.to_string();
-                    // info!("order_id string: {:?}", order_id_string);
-                    order_index_string.push_str(&order_id_string);
+                    let order_index_string = self.create_order_index_string(&proof_resource_address, &order_id);
                     // info!("Order_index_string {:?}", order_index_string);
-                    if let Some(order_reward_data) = self.order_rewards.get(&order_index_string)
-                    {
-                        for reward_name_tokens in order_reward_data.rewards.clone().into_values() {
-                            for (token_address_string, token_reward) in reward_name_tokens {
-                                let existing_token_total = token_totals.entry(token_address_string.clone()).or_insert(Decimal::ZERO).to_owned();
-                                token_totals.insert(
-                                    token_address_string.clone(), 
-                                    existing_token_total.checked_add(token_reward).expect(&format!("Could not add token reward {:?} to existing token total {:?}.", token_reward, existing_token_total))
-                                );
-                                // info!("Token totals: {:?}", token_totals);
-                            }
-                        };
-                        orders_to_remove.push(order_index_string.clone());
+                    self.take_order_rewards(&order_index_string, token_totals);
+                }
+            }
+        }
+
+        fn get_or_create_claim_token_manager(&mut self, token_address: &ResourceAddress) -> ResourceManager {
+            if let Some(claim_token_manager) = self.claim_token_managers.get(token_address) {
+                return claim_token_manager.clone();
+            }
+            let token_manager = ResourceManager::from(token_address.clone());
+            let divisibility = match token_manager.resource_type() {
+                ResourceType::Fungible { divisibility } => divisibility,
+                _ => panic!("Only fungible rewards can be converted into claim tokens."),
+            };
+            let token_symbol: String = token_manager.get_metadata("symbol").unwrap_or(None).unwrap_or(String::from(""));
+            // claim tokens can also be minted and burned with the nft authority badge, so they stay redeemable after a migration
+            let claim_token_rule = rule!(require(global_caller(Runtime::global_address())) || require(self.get_nft_authority_vault().resource_address()));
+            // the dapp definitions follow the dapp definitions of the component, so they can be updated like the metadata of the account rewards NFTs
+            let super_admin_rule = Runtime::global_component().get_role("super_admin").unwrap_or(rule!(deny_all));
+            let claim_token_metadata_rule = DexterClaimComponent::create_any_of_rule(super_admin_rule.clone(), claim_token_rule.clone());
+            let claim_token_manager = ResourceBuilder::new_fungible(OwnerRole::None)
+                .divisibility(divisibility)
+                .metadata(metadata! {
+                    roles {
+                        metadata_setter => claim_token_metadata_rule;
+                        metadata_setter_updater => super_admin_rule.clone();
+                        metadata_locker => super_admin_rule.clone();
+                        metadata_locker_updater => super_admin_rule;
+                    },
+                    init{
+                        "name" => format!("Claim Token {}", token_symbol), locked;
+                        "symbol" => format!("c{}", token_symbol), locked;
+                        "description" => format!("A token that can be redeemed 1:1 for unclaimed rewards of token {}.", self.create_resource_address_string(token_address)), locked;
+                        "tags" => vec!["Rewards"], locked;
+                        "dapp_definitions" => vec![GlobalAddress::from(self.dapp_definition.address())], updatable;
+                    }
+                })
+                .mint_roles(mint_roles! {
+                    minter => claim_token_rule.clone();
+                    minter_updater => rule!(deny_all);
+                })
+                .burn_roles(burn_roles! {
+                    burner => claim_token_rule.clone();
+                    burner_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
+            self.claim_token_managers.insert(token_address.clone(), claim_token_manager.clone());
+            self.claim_token_rewards.insert(claim_token_manager.address(), token_address.clone());
+            claim_token_manager
+        }
+
+        // Moves the rewards of the account NFT into token_totals and returns the payout splits of the NFT.
+        fn take_account_rewards(&mut self, account_id: &NonFungibleLocalId, token_totals: &mut HashMap<ResourceAddress, Decimal>) -> Vec<PayoutSplit> {
+            if self.frozen_accounts.get(account_id).is_some() {
+                // frozen rewards stay on the NFT until they are unfrozen or removed
+                return vec![];
+            }
+            let nft_data: AccountRewardsData = self.account_rewards_nft_manager.get_non_fungible_data(account_id);
+            // info!("Claim NFT Data: {:?}", nft_data);
+            if nft_data.rewards.len() > 0 {
+                let mut account_token_totals: HashMap<ResourceAddress, Decimal> = HashMap::new();
+                self.add_to_token_totals(nft_data.rewards, &mut account_token_totals);
+                // keep track of claimed rewards, upgrading the account data to the latest version if needed
+                let mut account_data = self.load_account_data(account_id);
+                for (token_address, token_reward) in account_token_totals.iter() {
+                    let total_claimed = account_data.total_claimed.get(token_address).cloned().unwrap_or(Decimal::ZERO);
+                    account_data.total_claimed.insert(token_address.clone(), total_claimed.checked_add(token_reward.clone()).expect("Could not add claimed rewards to total claimed."));
+                    let existing_token_total = token_totals.get(token_address).cloned().unwrap_or(Decimal::ZERO);
+                    token_totals.insert(token_address.clone(), existing_token_total.checked_add(token_reward.clone()).expect(&format!("Could not add token reward {:?} to existing token total {:?}.", token_reward, existing_token_total)));
+                }
+                account_data.last_claim_epoch = Some(Runtime::current_epoch().number());
+                self.update_account_nft_rewards(account_id, HashMap::new());
+                self.save_account_data(account_id, account_data);
+            }
+            self.load_account_data(account_id).payout_splits
+        }
+
+        fn load_account_data(&self, account_id: &NonFungibleLocalId) -> AccountDataV2 {
+            self.account_data.get(account_id).map(|account_data| account_data.clone().upgrade()).unwrap_or_default()
+        }
+
+        fn save_account_data(&mut self, account_id: &NonFungibleLocalId, account_data: AccountDataV2) {
+            self.account_data.insert(account_id.clone(), VersionedAccountData::V2(account_data));
+        }
+
+        // Withdraws the non-fungible rewards of the account NFT from the claim vaults.
+        fn take_account_nft_rewards(&mut self, account_id: &NonFungibleLocalId) -> Vec<Bucket> {
+            if self.frozen_accounts.get(account_id).is_some() {
+                return vec![];
+            }
+            let mut nft_totals: HashMap<ResourceAddress, Vec<NonFungibleLocalId>> = HashMap::new();
+            if let Some(nft_rewards) = self.account_nft_rewards.remove(account_id) {
+                for name_rewards in nft_rewards.into_values() {
+                    for (reward_token, nft_ids) in name_rewards {
+                        nft_totals.entry(reward_token).or_insert(vec![]).extend(nft_ids);
                     }
                 }
             }
-            for order in orders_to_remove {
-                self.order_rewards.remove(&order);
+            self.withdraw_nft_rewards(nft_totals)
+        }
+
+        fn withdraw_nft_rewards(&mut self, nft_totals: HashMap<ResourceAddress, Vec<NonFungibleLocalId>>) -> Vec<Bucket> {
+            let mut nft_buckets: Vec<Bucket> = vec![];
+            for (reward_token, nft_ids) in nft_totals {
+                let nft_ids_set: IndexSet<NonFungibleLocalId> = nft_ids.into_iter().collect();
+                let token_vault = self.claim_vaults.get_mut(&reward_token).expect(&format!("Could not find claim vault for NFT {:?}.", reward_token));
+                nft_buckets.push(token_vault.as_non_fungible().take_non_fungibles(&nft_ids_set).into());
+            }
+            nft_buckets
+        }
+
+        fn take_order_rewards(&mut self, order_index_string: &String, token_totals: &mut HashMap<ResourceAddress, Decimal>) {
+            if self.frozen_orders.get(order_index_string).is_some() {
+                return;
             }
-            // info!("Handled orders claims");
+            let order_reward_data = self.order_rewards.get(order_index_string).map(|order_data| order_data.clone());
+            if let Some(order_reward_data) = order_reward_data {
+                self.add_to_token_totals(order_reward_data.rewards, token_totals);
+                self.order_rewards.remove(order_index_string);
+            }
+        }
+
+        fn add_to_token_totals(&self, rewards: HashMap<String, HashMap<ResourceAddress, Decimal>>, token_totals: &mut HashMap<ResourceAddress, Decimal>) {
+            for reward_name_tokens in rewards.into_values() {
+                for (token_address, token_reward) in reward_name_tokens {
+                    let existing_token_total = token_totals.entry(token_address.clone()).or_insert(Decimal::ZERO).to_owned();
+                    token_totals.insert(
+                        token_address.clone(),
+                        existing_token_total.checked_add(token_reward).expect(&format!("Could not add token reward {:?} to existing token total {:?}.", token_reward, existing_token_total))
+                    );
+                    // info!("Token totals: {:?}", token_totals);
+                }
+            };
+        }
+
+        // The claim fee is calculated over all rewards of a token that are paid out in the same claim, so rewards that are paid out
+        // in parts, like the rewards of NFTs with payout splits, are only charged the minimum fee once. claimed_totals keeps the
+        // gross amount and the fee already paid out per token in the claim.
+        fn pay_out_rewards(&mut self, token_totals: HashMap<ResourceAddress, Decimal>, claimed_totals: &mut HashMap<ResourceAddress, (Decimal, Decimal)>) -> Vec<Bucket> {
+            let mut return_buckets: Vec<Bucket> = vec![];
+            let mut claimed_amounts: Vec<ClaimedTokenAmount> = vec![];
             for (token_address, token_reward) in token_totals {
                 if self.claim_vaults.get(&token_address).is_some() {
                     let mut token_vault = self.claim_vaults.get_mut(&token_address).unwrap();
                     assert!(token_vault.amount() >= token_reward, "Not enough tokens in component to pay for claimed rewards.");
-                    return_buckets.push(token_vault.take(token_reward));
+                    let mut claimed_bucket = token_vault.take(token_reward);
+                    drop(token_vault);
+                    let (claimed_gross, claimed_fee) = claimed_totals.get(&token_address).cloned().unwrap_or((Decimal::ZERO, Decimal::ZERO));
+                    let total_gross = claimed_gross.checked_add(token_reward).expect("Could not add token reward to claimed total.");
+                    let fee_amount = (self.calculate_claim_fee(&token_address, total_gross) - claimed_fee).max(Decimal::ZERO).min(token_reward);
+                    let mut fee_taken = Decimal::ZERO;
+                    if fee_amount > Decimal::ZERO {
+                        let fee_bucket = claimed_bucket.take_advanced(fee_amount, WithdrawStrategy::Rounded(RoundingMode::ToZero));
+                        fee_taken = fee_bucket.amount();
+                        self.deposit_to_fee_vault(fee_bucket);
+                    }
+                    claimed_totals.insert(token_address, (total_gross, claimed_fee + fee_taken));
+                    claimed_amounts.push(ClaimedTokenAmount {
+                        token_address,
+                        gross_amount: token_reward,
+                        fee_amount: fee_taken,
+                        net_amount: claimed_bucket.amount(),
+                    });
+                    return_buckets.push(claimed_bucket);
                 }
             }
+            if claimed_amounts.len() > 0 {
+                Runtime::emit_event(RewardsClaimedEvent { claimed_amounts });
+            }
             return_buckets
         }
 
-        pub fn deactivate(&mut self) {
-            self.active = false;
+        fn calculate_claim_fee(&self, token_address: &ResourceAddress, token_reward: Decimal) -> Decimal {
+            if let Some(claim_fee) = self.claim_fees.get(token_address) {
+                let fee_amount = (token_reward * Decimal::from(claim_fee.basis_points) / dec!(10000)).max(claim_fee.minimum);
+                fee_amount.min(token_reward)
+            } else {
+                Decimal::ZERO
+            }
         }
-        
-        pub fn activate(&mut self) {
-            self.active = true;
+
+        fn deposit_to_fee_vault(&mut self, fee_tokens: Bucket) {
+            let token_address = fee_tokens.resource_address();
+            if self.fee_vaults.get(&token_address).is_some() {
+                self.fee_vaults.get_mut(&token_address).unwrap().put(fee_tokens);
+            } else {
+                self.fee_vaults.insert(token_address, Vault::with_bucket(fee_tokens));
+            }
+        }
+
+        fn deposit_to_claim_vault(&mut self, tokens: Bucket) {
+            let token_address = tokens.resource_address();
+            if self.claim_vaults.get(&token_address).is_some() {
+                self.claim_vaults.get_mut(&token_address).unwrap().put(tokens);
+            } else {
+                self.claim_vaults.insert(token_address, Vault::with_bucket(tokens));
+                self.claim_tokens.push(token_address);
+            }
+        }
+
+        fn assert_cancellable_raffle(&self, raffle_id: u64) -> RaffleData {
+            let raffle = self.raffles.get(&raffle_id).expect(&format!("Could not find raffle {:?}.", raffle_id)).clone();
+            assert!(!raffle.drawn, "Raffle {:?} has already been drawn.", raffle_id);
+            assert!(!raffle.cancelled, "Raffle {:?} has already been cancelled.", raffle_id);
+            assert!(Runtime::current_epoch().number() > raffle.reveal_deadline_epoch, "Raffle {:?} can only be cancelled after the reveal deadline.", raffle_id);
+            raffle
+        }
+
+        fn get_raffle_prizes_total(&self, raffle: &RaffleData) -> Decimal {
+            let mut prizes_total = Decimal::ZERO;
+            for prize in raffle.prizes.iter() {
+                prizes_total = prizes_total.checked_add(*prize).expect("Could not add raffle prize to prizes total.");
+            }
+            prizes_total
+        }
+
+        // Amount based rewards are taken from the claim vaults by amount, which would hand out arbitrary NFTs of a non-fungible vault.
+        fn assert_fungible_reward_token(&self, reward_token: &ResourceAddress) {
+            assert!(reward_token.is_fungible(), "Only fungible tokens can be used as amount based rewards, use NFT rewards for non-fungible tokens.");
+        }
+
+        fn create_any_of_rule(rule1: AccessRule, rule2: AccessRule) -> AccessRule {
+            match (rule1, rule2) {
+                (AccessRule::AllowAll, _) | (_, AccessRule::AllowAll) => AccessRule::AllowAll,
+                (AccessRule::DenyAll, other_rule) | (other_rule, AccessRule::DenyAll) => other_rule,
+                (AccessRule::Protected(node1), AccessRule::Protected(node2)) => AccessRule::Protected(AccessRuleNode::AnyOf(vec![node1, node2])),
+            }
+        }
+
+        fn assert_account_owner(&self, account_id: &NonFungibleLocalId) {
+            let account_address = self.get_account_address(account_id);
+            Runtime::assert_access_rule(self.create_account_owner_rule(&account_address));
         }
 
-        fn load_account_rewards(&mut self, reward_name: String, reward_token: ResourceAddress, account_rewards: Vec<(ComponentAddress,Decimal)>, add: bool) -> Decimal {
-            let mut total_token_change = Decimal::ZERO;
+        // Accounts list the keys that own them in their owner_keys metadata, which can only be set by the account owner.
+        // Accounts that have not been securified are also owned by the signature of the key their address was derived from,
+        // and securified accounts by their account owner badge.
+        fn create_account_owner_rule(&self, account_address: &ComponentAddress) -> AccessRule {
+            let node_id = account_address.as_node_id();
+            let owner_badge = NonFungibleGlobalId::new(ACCOUNT_OWNER_BADGE, NonFungibleLocalId::bytes(node_id.0.to_vec()).expect("Could not create account owner badge id."));
+            let mut owner_proofs: Vec<ResourceOrNonFungible> = vec![owner_badge.into()];
+            match node_id.entity_type() {
+                Some(EntityType::GlobalVirtualSecp256k1Account) => {
+                    owner_proofs.push(NonFungibleGlobalId::from_public_key_hash(Secp256k1PublicKeyHash(node_id.0[1..].try_into().unwrap())).into());
+                }
+                Some(EntityType::GlobalVirtualEd25519Account) => {
+                    owner_proofs.push(NonFungibleGlobalId::from_public_key_hash(Ed25519PublicKeyHash(node_id.0[1..].try_into().unwrap())).into());
+                }
+                _ => {}
+            }
+            let account: Global<Account> = Global::from(account_address.clone());
+            let owner_keys: Vec<PublicKeyHash> = account.get_metadata("owner_keys").unwrap_or(None).unwrap_or(vec![]);
+            for owner_key in owner_keys {
+                owner_proofs.push(NonFungibleGlobalId::from_public_key_hash(owner_key).into());
+            }
+            rule!(require_any_of(owner_proofs))
+        }
+
+        fn load_account_rewards(&mut self, reward_name: String, reward_token: ResourceAddress, account_rewards: Vec<(ComponentAddress,Decimal)>, add: bool) -> Vec<RewardEntryReport> {
+            let mut entries: Vec<RewardEntryReport> = vec![];
             for (account_address, account_reward) in account_rewards {
-                let mut skip_account = false;
                 let account_id = NonFungibleLocalId::string(self.create_account_id(&account_address)).expect(&format!("Could not convert {:?} into a valid NFT ID", account_address));
                 // info!("Account NFT id: {:?}", account_id);
-                let existing_account_data: AccountRewardsData;
-                if self.account_rewards_nft_manager.non_fungible_exists(&account_id) {
-                    existing_account_data = self.account_rewards_nft_manager.get_non_fungible_data(&account_id)
+                let mut account_nft_exists = self.account_rewards_nft_manager.non_fungible_exists(&account_id);
+                if !account_nft_exists && add {
+                    account_nft_exists = self.mint_account_nft(&account_address, &account_id);
+                }
+                if account_nft_exists {
+                    let mut existing_account_data: AccountRewardsData = self.account_rewards_nft_manager.get_non_fungible_data(&account_id);
+                    // info!("Existing account data: {:?}", existing_account_data);
+                    let token_change = self.update_rewards(&mut existing_account_data.rewards, &reward_name, &reward_token, account_reward, add);
+                    entries.push(self.create_entry_report(RewardEntry::Account(account_address.clone()), account_reward, token_change, add));
+                    self.update_account_nft_rewards(&account_id, existing_account_data.rewards);
                 } else {
-                    // account_nft_exists = false;
-                    existing_account_data = AccountRewardsData {
-                        account_address: self.create_component_address_string(&account_address),
-                        rewards: HashMap::new()
-                    };
-                    if add {
-                        let new_nft = self.account_rewards_nft_manager.mint_non_fungible(&account_id, existing_account_data.clone()).as_non_fungible();
-                        let account_component: Global<AnyComponent> = Global::from(account_address);
-                        let returned_bucket: Option<NonFungibleBucket> = account_component.call::<(NonFungibleBucket,Option<ResourceOrNonFungible>),_>("try_deposit_or_refund", &(new_nft, None));
-                        if let Some(returned_nft) = returned_bucket {
-                            // info!("Could not deposit nft to account {:?}", account_address);
-                            returned_nft.burn();
-                            skip_account = true;
-                        };
-                        // info!("Account received NFT");
+                    // the account refused the NFT deposit, so its rewards are kept as pending rewards until it can receive the NFT
+                    let mut pending_rewards = self.pending_rewards.get(&account_address).map(|rewards| rewards.clone()).unwrap_or(HashMap::new());
+                    if !add && pending_rewards.len() == 0 {
+                        entries.push(RewardEntryReport {
+                            entry: RewardEntry::Account(account_address.clone()),
+                            requested_amount: account_reward,
+                            applied_amount: Decimal::ZERO,
+                            status: RewardEntryStatus::SkippedNotFound,
+                        });
+                        continue;
+                    }
+                    let token_change = self.update_rewards(&mut pending_rewards, &reward_name, &reward_token, account_reward, add);
+                    if pending_rewards.len() > 0 {
+                        self.pending_rewards.insert(account_address.clone(), pending_rewards);
                     } else {
-                        skip_account = true;
-                    }
-                }
-                // info!("Existing account data: {:?}", existing_account_data);
-                let mut existing_account_rewards = existing_account_data.rewards;
-                if !skip_account {
-                    let mut existing_name_data = existing_account_rewards
-                        .entry(reward_name.clone())
-                        .or_insert(HashMap::new())
-                        .clone();
-                    // info!("Existing name data: {:?}", existing_name_data);
-                    let mut existing_token_total = existing_name_data
-                        .entry(reward_token.clone())
-                        .or_insert(Decimal::ZERO)
-                        .clone();
-                    let mut token_change = account_reward.clone();
+                        self.pending_rewards.remove(&account_address);
+                    }
                     if add {
-                        existing_token_total =
-                            existing_token_total.checked_add(token_change).expect(
-                                "Could not add new token reward to existing token total",
-                            );
-                    } else {
-                        token_change =
-                            existing_token_total.min(account_reward.clone());
-                        existing_token_total =
-                            existing_token_total.checked_sub(token_change).expect(
-                                "Could not remove token reward from existing token total",
-                            );
-                    }
-                    if existing_token_total > Decimal::ZERO {
-                        existing_name_data
-                            .insert(reward_token.clone(), existing_token_total.to_owned());
+                        entries.push(RewardEntryReport {
+                            entry: RewardEntry::Account(account_address.clone()),
+                            requested_amount: account_reward,
+                            applied_amount: token_change,
+                            status: RewardEntryStatus::DepositRefusedPending,
+                        });
+                        Runtime::emit_event(PendingRewardsEvent {
+                            account_address,
+                            reward_name: reward_name.clone(),
+                            reward_token,
+                            amount: token_change,
+                        });
                     } else {
-                        existing_name_data.remove(&reward_token);
-                    }
-                    // info!("Existing name data (after update) {:?}", existing_name_data);
-                    total_token_change = total_token_change + token_change;
-                    // info!("Total token reward: {:?}", total_token_change);
-                    if existing_name_data.len() > 0 {
-                        existing_account_rewards
-                            .insert(reward_name.clone(), existing_name_data.to_owned());
-                    } else {
-                        existing_account_rewards.remove(&reward_name);
+                        entries.push(self.create_entry_report(RewardEntry::Account(account_address.clone()), account_reward, token_change, add));
                     }
-                    // info!("Existing account rewards: {:?}", existing_account_rewards);
-                    self.account_rewards_nft_manager.update_non_fungible_data::<HashMap<String, HashMap<ResourceAddress, Decimal>>>(&account_id, "rewards", existing_account_rewards);
                 }
             }
-            total_token_change
+            entries
         }
 
         fn load_orders_rewards(
@@ -452,69 +2419,158 @@ mod dexter_claim_component {
             reward_name: String, reward_token: ResourceAddress,
             orders_data: Vec<JsonPairOrderRewards>,
             add: bool,
-        ) -> Decimal {
-            let mut total_token_change = Decimal::ZERO;
+        ) -> Vec<RewardEntryReport> {
+            let mut entries: Vec<RewardEntryReport> = vec![];
             for pair_order_rewards_data in &orders_data {
                 let pair_address_string = pair_order_rewards_data.pair_receipt_address.clone();
                 for (order_id, order_reward_amount) in &pair_order_rewards_data.pair_rewards {
-                    let mut skip_order = false;
                     let mut order_id_string = pair_address_string.clone();
                     order_id_string.push_str("#");
                     order_id_string.push_str(&order_id.to_string());
                     order_id_string.push_str("#");
-                    let mut existing_order_data: OrderRewardsData;
-                    if let Some(existing_data) = self.order_rewards.get(&order_id_string) {
-                        existing_order_data = existing_data.clone();
-                    } else {
-                        existing_order_data = OrderRewardsData {
-                            order_id: order_id_string.clone(),
-                            rewards: HashMap::new(),
-                        };
-                        if !add { skip_order = true;}
-                    }
-                    let mut existing_order_rewards = existing_order_data.rewards;
-                    if !skip_order {
-                        let mut existing_name_data = existing_order_rewards
-                            .entry(reward_name.clone())
-                            .or_insert(HashMap::new())
-                            .clone();
-                        let mut existing_token_total = existing_name_data
-                            .entry(reward_token.clone())
-                            .or_insert(Decimal::ZERO)
-                            .clone();
-                        let mut token_change = order_reward_amount.clone();
-                        if add {
-                            existing_token_total =
-                                existing_token_total.checked_add(token_change).expect(
-                                    "Could not add new token reward to existing token total",
-                                );
-                        } else {
-                            token_change =
-                                existing_token_total.min(order_reward_amount.clone());
-                            existing_token_total =
-                                existing_token_total.checked_sub(token_change).expect(
-                                    "Could not remove token reward from existing token total",
-                                );
-                        }
-                        if existing_token_total > Decimal::ZERO {
-                            existing_name_data
-                                .insert(reward_token.clone(), existing_token_total.to_owned());
-                        } else {
-                            existing_name_data.remove(&reward_token);
-                        }
-                        total_token_change = total_token_change + token_change;
-                        if existing_name_data.len() > 0 {
-                            existing_order_rewards
-                                .insert(reward_name.clone(), existing_name_data.to_owned());
-                        } else {
-                            existing_order_rewards.remove(&reward_name);
-                        }
-                        existing_order_data.rewards = existing_order_rewards;
-                        self.order_rewards.insert(order_id_string, existing_order_data);
-                    }
+                    entries.push(self.load_order_rewards(&reward_name, &reward_token, order_id_string, order_reward_amount.clone(), add));
+                }
+            }
+            entries
+        }
+
+        fn load_order_rewards(
+            &mut self,
+            reward_name: &String,
+            reward_token: &ResourceAddress,
+            order_id_string: String,
+            order_reward_amount: Decimal,
+            add: bool,
+        ) -> RewardEntryReport {
+            let mut existing_order_data: OrderRewardsData;
+            if let Some(existing_data) = self.order_rewards.get(&order_id_string) {
+                existing_order_data = existing_data.clone();
+            } else {
+                if !add {
+                    return RewardEntryReport {
+                        entry: RewardEntry::Order(order_id_string),
+                        requested_amount: order_reward_amount,
+                        applied_amount: Decimal::ZERO,
+                        status: RewardEntryStatus::SkippedNotFound,
+                    };
                 }
+                existing_order_data = OrderRewardsData {
+                    order_id: order_id_string.clone(),
+                    rewards: HashMap::new(),
+                };
+            }
+            let token_change = self.update_rewards(&mut existing_order_data.rewards, reward_name, reward_token, order_reward_amount, add);
+            let entry_report = self.create_entry_report(RewardEntry::Order(order_id_string.clone()), order_reward_amount, token_change, add);
+            self.order_rewards.insert(order_id_string, existing_order_data);
+            entry_report
+        }
+
+        fn create_entry_report(&self, entry: RewardEntry, requested_amount: Decimal, applied_amount: Decimal, add: bool) -> RewardEntryReport {
+            let status = if !add && applied_amount < requested_amount {
+                RewardEntryStatus::Clamped
+            } else {
+                RewardEntryStatus::Applied
+            };
+            RewardEntryReport {
+                entry,
+                requested_amount,
+                applied_amount,
+                status,
+            }
+        }
+
+        fn create_rewards_report(&self, reward_name: String, reward_token: ResourceAddress, entries: Vec<RewardEntryReport>) -> RewardsReport {
+            let mut total_amount = Decimal::ZERO;
+            for entry in entries.iter() {
+                total_amount = total_amount.checked_add(entry.applied_amount).expect("Could not add entry amount to rewards report total.");
+            }
+            RewardsReport {
+                reward_name,
+                reward_token,
+                total_amount,
+                entries,
+            }
+        }
+
+        // Adds or removes a token reward in a rewards map and returns the amount of tokens that was actually changed.
+        // Removals are limited to the existing token reward.
+        fn update_rewards(
+            &self,
+            rewards: &mut HashMap<String, HashMap<ResourceAddress, Decimal>>,
+            reward_name: &String,
+            reward_token: &ResourceAddress,
+            token_reward: Decimal,
+            add: bool,
+        ) -> Decimal {
+            let mut existing_name_data = rewards
+                .get(reward_name)
+                .map(|name_data| name_data.clone())
+                .unwrap_or(HashMap::new());
+            // info!("Existing name data: {:?}", existing_name_data);
+            let mut existing_token_total = existing_name_data
+                .get(reward_token)
+                .map(|token_total| token_total.clone())
+                .unwrap_or(Decimal::ZERO);
+            let mut token_change = token_reward.clone();
+            if add {
+                existing_token_total =
+                    existing_token_total.checked_add(token_change).expect(
+                        "Could not add new token reward to existing token total",
+                    );
+            } else {
+                token_change =
+                    existing_token_total.min(token_reward.clone());
+                existing_token_total =
+                    existing_token_total.checked_sub(token_change).expect(
+                        "Could not remove token reward from existing token total",
+                    );
+            }
+            if existing_token_total > Decimal::ZERO {
+                existing_name_data
+                    .insert(reward_token.clone(), existing_token_total.to_owned());
+            } else {
+                existing_name_data.remove(reward_token);
             }
-            total_token_change
+            // info!("Existing name data (after update) {:?}", existing_name_data);
+            if existing_name_data.len() > 0 {
+                rewards.insert(reward_name.clone(), existing_name_data);
+            } else {
+                rewards.remove(reward_name);
+            }
+            token_change
+        }
+
+        // The account rewards NFTs are managed with the nft authority badge, so a successor can keep managing the NFTs of its predecessor.
+        fn get_nft_authority_vault(&self) -> &Vault {
+            match &self.predecessor_nft_authority_badge {
+                Some(predecessor_badge) => predecessor_badge,
+                None => &self.nft_authority_badge,
+            }
+        }
+
+        fn update_account_nft_rewards(&self, account_id: &NonFungibleLocalId, rewards: HashMap<String, HashMap<ResourceAddress, Decimal>>) {
+            let nft_manager = self.account_rewards_nft_manager;
+            self.get_nft_authority_vault().as_fungible().authorize_with_amount(1, || nft_manager.update_non_fungible_data(account_id, "rewards", rewards));
+        }
+
+        // Mints a new rewards NFT and sends it to the account. Returns false if the account refused the NFT.
+        fn mint_account_nft(&mut self, account_address: &ComponentAddress, account_id: &NonFungibleLocalId) -> bool {
+            let new_account_data = AccountRewardsData {
+                account_address: self.create_component_address_string(account_address),
+                rewards: HashMap::new(),
+            };
+            let nft_manager = self.account_rewards_nft_manager;
+            let nft_authority_vault = self.get_nft_authority_vault().as_fungible();
+            let new_nft = nft_authority_vault.authorize_with_amount(1, || nft_manager.mint_non_fungible(account_id, new_account_data)).as_non_fungible();
+            let account_component: Global<AnyComponent> = Global::from(account_address.clone());
+            let returned_bucket: Option<NonFungibleBucket> = account_component.call::<(NonFungibleBucket,Option<ResourceOrNonFungible>),_>("try_deposit_or_refund", &(new_nft, None));
+            if let Some(returned_nft) = returned_bucket {
+                // info!("Could not deposit nft to account {:?}", account_address);
+                nft_authority_vault.authorize_with_amount(1, || returned_nft.burn());
+                return false;
+            };
+            // info!("Account received NFT");
+            true
         }
 
         fn parse_orders_rewards_data(&self, orders_rewards_data_str: String) -> Vec<JsonPairOrderRewards> {
@@ -667,6 +2723,41 @@ mod dexter_claim_component {
             }
         }
 
+        fn create_order_index_string(&self, order_receipt_address: &ResourceAddress, order_id: &NonFungibleLocalId) -> String {
+            let mut order_index_string = self.create_resource_address_string(order_receipt_address);
+            order_index_string.push_str(&order_id.to_string());
+            order_index_string
+        }
+
+        // Account NFT ids are created from the account address, so the account of an NFT can always be recovered from its id.
+        fn get_account_address(&self, account_id: &NonFungibleLocalId) -> ComponentAddress {
+            let id_string = match account_id {
+                NonFungibleLocalId::String(id) => id.value().to_string(),
+                _ => panic!("Could not find the account related to NFT {:?}.", account_id),
+            };
+            let account_address = if self.env == "local" {
+                ComponentAddress::try_from_hex(&id_string)
+            } else {
+                // the id is the data part of the bech32m address: 5 bit characters followed by a 6 character checksum
+                let bech32_charset = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+                let data_length = id_string.len().checked_sub(6).expect(&format!("Could not find the account related to NFT {:?}.", account_id));
+                let mut address_bytes: Vec<u8> = vec![];
+                let mut accumulator: u32 = 0;
+                let mut bits: u32 = 0;
+                for id_char in id_string[..data_length].chars() {
+                    let char_value = bech32_charset.find(id_char).expect(&format!("Could not find the account related to NFT {:?}.", account_id)) as u32;
+                    accumulator = ((accumulator << 5) | char_value) & 0xfff;
+                    bits += 5;
+                    if bits >= 8 {
+                        bits -= 8;
+                        address_bytes.push(((accumulator >> bits) & 0xff) as u8);
+                    }
+                }
+                ComponentAddress::try_from(address_bytes.as_slice()).ok()
+            };
+            account_address.expect(&format!("Could not find the account related to NFT {:?}.", account_id))
+        }
+
         fn create_account_id(&self, address: &ComponentAddress) -> String {
             if self.env == "local" {
                 address.to_hex()
@@ -1 +1,3 @@
 pub mod claim;
+#[cfg(feature = "test")]
+pub mod mock_pair;
@@ -0,0 +1,61 @@
+use scrypto::prelude::*;
+
+#[derive(ScryptoSbor, NonFungibleData, Clone, Debug)]
+pub struct MockOrderReceiptData {
+    pub volume: Decimal,
+}
+
+// Minimal stand-in for a DEX pair, used in tests to exercise the record_fill hook of the claim component.
+#[blueprint]
+mod mock_pair {
+    struct MockPair {
+        order_receipt_manager: ResourceManager,
+        claim_component: Global<AnyComponent>,
+        order_counter: u64,
+        deposits: Vec<Vault>,
+    }
+
+    impl MockPair {
+        pub fn new(claim_component: ComponentAddress) -> Global<MockPair> {
+            let (address_reservation, component_address) =
+                Runtime::allocate_component_address(<MockPair>::blueprint_id());
+            let order_receipt_manager = ResourceBuilder::new_integer_non_fungible::<MockOrderReceiptData>(OwnerRole::None)
+                .mint_roles(mint_roles! {
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
+            Self {
+                order_receipt_manager,
+                claim_component: Global::from(claim_component),
+                order_counter: 0,
+                deposits: vec![],
+            }
+            .instantiate()
+            .prepare_to_globalize(OwnerRole::None)
+            .with_address(address_reservation)
+            .globalize()
+        }
+
+        // Mints an order receipt for a new order that is filled immediately and records the fill with the claim component.
+        pub fn place_order(&mut self, volume: Decimal) -> Bucket {
+            self.order_counter += 1;
+            let order_id = NonFungibleLocalId::integer(self.order_counter);
+            let order_receipt = self.order_receipt_manager.mint_non_fungible(&order_id, MockOrderReceiptData { volume });
+            let order_global_id = NonFungibleGlobalId::new(self.order_receipt_manager.address(), order_id);
+            self.claim_component.call::<(NonFungibleGlobalId, Decimal), Decimal>("record_fill", &(order_global_id, volume));
+            order_receipt
+        }
+
+        // Holds the tokens like the pair holds the tokens of an order. Used in tests as a target for forwarded claims.
+        pub fn deposit(&mut self, tokens: Bucket) -> Decimal {
+            let amount = tokens.amount();
+            self.deposits.push(Vault::with_bucket(tokens));
+            amount
+        }
+
+        pub fn get_order_receipt_address(&self) -> ResourceAddress {
+            self.order_receipt_manager.address()
+        }
+    }
+}
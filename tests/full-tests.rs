@@ -1,5 +1,9 @@
 // use claim_component::claim::dexter_claim_component::DexterClaimComponent;
-use claim_component::claim::AccountRewardsData;
+use claim_component::claim::{
+    AccountDataV2, AccountRewardsData, AccrualConfig, ClaimCursor, ClaimDelegate, FaucetData,
+    OrderRewardsData, PendingRewardsEvent, RaffleTicket, RewardEntry, RewardEntryStatus,
+    RewardsClaimedEvent, RewardsFrozenEvent, RewardsReport, RewardsUnfrozenEvent,
+};
 use scrypto::prelude::DIVISIBILITY_NONE;
 use scrypto::prelude::*;
 use scrypto_test::prelude::*;
@@ -179,6 +183,143 @@ fn add_orders_rewards_test() {
     );
 }
 
+#[test]
+fn remove_orders_rewards_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, _claim_token_address) =
+        setup_component(&main_account, XRD, dextr_admin_token, &mut test_runner);
+    let test_str = build_orders_test_str(&mut test_runner);
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), XRD, dec!("2000"))
+        .take_all_from_worktop(XRD, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_orders_rewards",
+                manifest_args!(
+                    String::from("Trading Rewards"),
+                    dextr_token.clone(),
+                    test_str.clone(),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let _result = receipt.expect_commit_success();
+
+    let order_ids: Vec<String> = vec![
+        String::from("pair1_address#1#"),
+        String::from("pair1_address#2#"),
+        String::from("pair2_address#1#"),
+        String::from("pair2_address#2#"),
+    ];
+    let count_trading_rewards = |test_runner: &mut TestRunner<NoExtension, InMemorySubstateDatabase>| {
+        let tx_manifest = ManifestBuilder::new()
+            .call_method(
+                component_address,
+                "get_orders_rewards",
+                manifest_args!(order_ids.clone()),
+            )
+            .build();
+        let receipt = test_runner.execute_manifest_ignoring_fee(tx_manifest, vec![]);
+        let orders_rewards: Vec<OrderRewardsData> = receipt.expect_commit_success().output(0);
+        orders_rewards
+            .iter()
+            .filter(|order_rewards| {
+                order_rewards
+                    .rewards
+                    .get("Trading Rewards")
+                    .and_then(|name_rewards| name_rewards.get(&XRD))
+                    .map(|reward| *reward > Decimal::ZERO)
+                    .unwrap_or(false)
+            })
+            .count()
+    };
+    let orders_with_rewards = count_trading_rewards(&mut test_runner);
+    assert!(
+        orders_with_rewards == 4,
+        "Expected 4 orders with trading rewards, but found {:?}",
+        orders_with_rewards
+    );
+
+    // removing with a reward name that was never added should not remove anything
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "remove_orders_rewards",
+            manifest_args!(
+                String::from("Market Order Rewards"),
+                dextr_token.clone(),
+                test_str.clone()
+            ),
+        )
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let _result = receipt.expect_commit_success();
+    let account_balance = test_runner.get_component_balance(main_account.2.clone(), XRD);
+    assert!(
+        account_balance == dec!("8839.54"),
+        "Expected Account Balance of 8839.54, but found {:?}",
+        account_balance
+    );
+    let orders_with_rewards = count_trading_rewards(&mut test_runner);
+    assert!(
+        orders_with_rewards == 4,
+        "Expected 4 orders with trading rewards, but found {:?}",
+        orders_with_rewards
+    );
+
+    // removing with the reward name used to add the rewards should return all tokens
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "remove_orders_rewards",
+            manifest_args!(
+                String::from("Trading Rewards"),
+                dextr_token.clone(),
+                test_str.clone()
+            ),
+        )
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let _result = receipt.expect_commit_success();
+    let account_balance = test_runner.get_component_balance(main_account.2.clone(), XRD);
+    assert!(
+        account_balance == dec!("10000"),
+        "Expected Account Balance of 10000, but found {:?}",
+        account_balance
+    );
+    let orders_with_rewards = count_trading_rewards(&mut test_runner);
+    assert!(
+        orders_with_rewards == 0,
+        "Expected no orders with trading rewards, but found {:?}",
+        orders_with_rewards
+    );
+}
+
 #[test]
 pub fn add_liquidity_and_then_trading_rewards_test() {
     let mut test_runner = TestRunnerBuilder::new().without_trace().build();
@@ -1157,141 +1298,3648 @@ pub fn remove_accounts_rewards_overflow_test() {
 }
 
 #[test]
-pub fn change_dapp_def_test() {
+pub fn payout_splits_test() {
     let mut test_runner = TestRunnerBuilder::new().without_trace().build();
     let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
     let dextr_admin_token =
         test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
-    let (_component_address, dapp_def_address, _claim_token_address) =
-        setup_component(&main_account, XRD, dextr_admin_token, &mut test_runner);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    let (_pubkey2, _, team_account_address) = test_runner.new_allocated_account();
 
-    // change metadata with authorisation - should succeed
     let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
         .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
-        .set_metadata(dapp_def_address, "name", "DeXter Claim Component 2")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("100"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
         .drop_all_proofs()
-        .try_deposit_entire_worktop_or_abort(main_account.2, None)
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
         .build();
     let receipt = test_runner.execute_manifest_ignoring_fee(
         tx_manifest,
         vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
     );
-    // println!("Receipt: {:?}", receipt);
-    let _result = receipt.expect_commit_success();
+    receipt.expect_commit_success();
 
-    // change metadata without authorisation = should fail
+    let account_nft_id = NonFungibleLocalId::string(account1_address.to_hex()).unwrap();
     let tx_manifest = ManifestBuilder::new()
-        .set_metadata(dapp_def_address, "name", "DeXter Claim Component 2")
-        .try_deposit_entire_worktop_or_abort(main_account.2, None)
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![account_nft_id.clone()],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "set_payout_splits",
+                manifest_args!(
+                    lookup.proof("account_nft"),
+                    vec!(
+                        (account1_address, dec!("90")),
+                        (team_account_address, dec!("10"))
+                    )
+                ),
+            )
+        })
         .build();
     let receipt = test_runner.execute_manifest_ignoring_fee(
         tx_manifest,
-        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
     );
-    // println!("Receipt: {:?}", receipt);
-    let _result = receipt.expect_commit_failure();
-}
-
-#[test]
-pub fn change_admin_role_test() {
-    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
-    let main_account = test_runner.new_allocated_account();
-    let _second_account = test_runner.new_allocated_account();
-    let dextr_admin_token =
-        test_runner.create_fungible_resource(dec!(2), DIVISIBILITY_NONE, main_account.2);
-    let (component_address, _dapp_def_address, _claim_token_address) =
-        setup_component(&main_account, XRD, dextr_admin_token, &mut test_runner);
-
-    // change admin role without authorisation - should fail
+    receipt.expect_commit_success();
     let tx_manifest = ManifestBuilder::new()
-        .set_main_role(
-            component_address.clone(),
-            "admin",
-            rule!(require_amount(2, dextr_admin_token.clone())),
+        .call_method(
+            component_address,
+            "get_account_data",
+            manifest_args!(account_nft_id.clone()),
         )
-        .try_deposit_entire_worktop_or_abort(main_account.2, None)
         .build();
-    let receipt = test_runner.execute_manifest_ignoring_fee(
-        tx_manifest,
-        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    let receipt = test_runner.execute_manifest_ignoring_fee(tx_manifest, vec![]);
+    let account_data: AccountDataV2 = receipt.expect_commit_success().output(0);
+    assert!(
+        account_data.payout_splits.len() == 2,
+        "Expected 2 payout splits, but found {:?}",
+        account_data.payout_splits
     );
-    // println!("Receipt: {:?}", receipt);
-    let _result = receipt.expect_commit_failure();
 
-    // change admin role with authorisation - should succeed
+    let order_proofs: Vec<ManifestProof> = vec![];
     let tx_manifest = ManifestBuilder::new()
-        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
-        .set_main_role(
-            component_address.clone(),
-            "admin",
-            rule!(require_amount(2, dextr_admin_token.clone())),
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![account_nft_id],
         )
-        .drop_all_proofs()
-        .try_deposit_entire_worktop_or_abort(main_account.2, None)
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "claim_rewards",
+                manifest_args!(vec!(lookup.proof("account_nft")), order_proofs),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
         .build();
     let receipt = test_runner.execute_manifest_ignoring_fee(
         tx_manifest,
-        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
     );
-    // println!("Receipt: {:?}", receipt);
-    let _result = receipt.expect_commit_success();
+    receipt.expect_commit_success();
+
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10090"),
+        "Expected Account Balance of 10090, but found {:?}",
+        account_balance
+    );
+    let team_account_balance = test_runner.get_component_balance(team_account_address, XRD);
+    assert!(
+        team_account_balance == dec!("10010"),
+        "Expected Team Account Balance of 10010, but found {:?}",
+        team_account_balance
+    );
+}
+
+#[test]
+pub fn delegated_claim_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    let (bot_pubkey, _, bot_account_address) = test_runner.new_allocated_account();
+    set_owner_keys(&mut test_runner, bot_account_address, bot_pubkey);
 
-    // try to run admins-only protected method - should fail
-    let (test_str, _test_accounts) = build_accounts_test_str(&mut test_runner);
     let tx_manifest = ManifestBuilder::new()
-        .withdraw_from_account(main_account.2, XRD, dec!("2000"))
-        .take_all_from_worktop(XRD, "xrd_bucket")
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
         .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
         .with_name_lookup(|builder, lookup| {
             builder.call_method(
                 component_address,
-                "add_rewards",
+                "add_account_rewards",
                 manifest_args!(
-                    String::from(test_str.trim()),
-                    vec!(lookup.bucket("xrd_bucket"))
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("100"))),
+                    lookup.bucket("dextr_bucket")
                 ),
             )
         })
         .drop_all_proofs()
-        .try_deposit_entire_worktop_or_abort(main_account.2, None)
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
         .build();
-
     let receipt = test_runner.execute_manifest_ignoring_fee(
         tx_manifest,
         vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
     );
-    println!("Receipt: {:?}", receipt);
-    let _result = receipt.expect_commit_failure();
+    receipt.expect_commit_success();
 
-    // try to run admins-only protected method again with proper authorisation - should succeed
-    let (test_str, _test_accounts) = build_accounts_test_str(&mut test_runner);
+    let account_nft_id = NonFungibleLocalId::string(account1_address.to_hex()).unwrap();
     let tx_manifest = ManifestBuilder::new()
-        .withdraw_from_account(main_account.2, XRD, dec!("2000"))
-        .take_all_from_worktop(XRD, "xrd_bucket")
-        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 2)
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![account_nft_id.clone()],
+        )
+        .pop_from_auth_zone("account_nft")
         .with_name_lookup(|builder, lookup| {
             builder.call_method(
                 component_address,
-                "add_rewards",
+                "grant_claim_delegate",
                 manifest_args!(
-                    String::from(test_str.trim()),
-                    vec!(lookup.bucket("xrd_bucket"))
+                    lookup.proof("account_nft"),
+                    ClaimDelegate::Account(bot_account_address),
+                    1000u64
                 ),
             )
         })
-        .drop_all_proofs()
-        .try_deposit_entire_worktop_or_abort(main_account.2, None)
         .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
 
+    // the NFT holder can not act as their own delegate
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            component_address,
+            "delegated_claim",
+            manifest_args!(vec!(account_nft_id.clone())),
+        )
+        .build();
     let receipt = test_runner.execute_manifest_ignoring_fee(
         tx_manifest,
-        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_failure();
+
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            component_address,
+            "delegated_claim",
+            manifest_args!(vec!(account_nft_id)),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&bot_pubkey)],
+    );
+    receipt.expect_commit_success();
+
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10100"),
+        "Expected Account Balance of 10100, but found {:?}",
+        account_balance
+    );
+    let bot_account_balance = test_runner.get_component_balance(bot_account_address, XRD);
+    assert!(
+        bot_account_balance == dec!("10000"),
+        "Expected Bot Account Balance of 10000, but found {:?}",
+        bot_account_balance
     );
-    // println!("Receipt: {:?}", receipt);
-    let _result = receipt.expect_commit_success();
 }
 
-fn check_account_reward_amount(
+#[test]
+pub fn claim_nft_rewards_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let reward_nft_address = test_runner.create_non_fungible_resource(main_account.2);
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), reward_nft_address, dec!("3"))
+        .take_all_from_worktop(reward_nft_address, "nft_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_nft_rewards",
+                manifest_args!(
+                    String::from("Event Tickets"),
+                    vec!((account1_address, vec!(NonFungibleLocalId::integer(1)))),
+                    lookup.bucket("nft_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let main_account_nfts = test_runner.get_component_balance(main_account.2, reward_nft_address);
+    assert!(
+        main_account_nfts == dec!("2"),
+        "Expected 2 NFTs to be returned, but found {:?}",
+        main_account_nfts
+    );
+
+    let order_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![NonFungibleLocalId::string(account1_address.to_hex()).unwrap()],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "claim_rewards",
+                manifest_args!(vec!(lookup.proof("account_nft")), order_proofs),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let account_nfts = test_runner.get_component_balance(account1_address, reward_nft_address);
+    assert!(
+        account_nfts == dec!("1"),
+        "Expected 1 rewarded NFT in account, but found {:?}",
+        account_nfts
+    );
+
+    // NFT rewards for an account that refuses the account rewards NFT are kept until the account claims them
+    let (pubkey2, _, account2_address) = test_runner.new_allocated_account();
+    set_owner_keys(&mut test_runner, account2_address, pubkey2);
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            account2_address,
+            "set_resource_preference",
+            manifest_args!(claim_token_address, ResourcePreference::Disallowed),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey2)],
+    );
+    receipt.expect_commit_success();
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_non_fungibles_from_account(
+            main_account.2.clone(),
+            reward_nft_address,
+            vec![NonFungibleLocalId::integer(2)],
+        )
+        .take_all_from_worktop(reward_nft_address, "nft_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_nft_rewards",
+                manifest_args!(
+                    String::from("Event Tickets"),
+                    vec!((account2_address, vec!(NonFungibleLocalId::integer(2)))),
+                    lookup.bucket("nft_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let account2_claim_nfts = test_runner.get_component_balance(account2_address, claim_token_address);
+    assert!(
+        account2_claim_nfts == dec!("0"),
+        "Expected no account rewards NFT in account 2, but found {:?}",
+        account2_claim_nfts
+    );
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            component_address,
+            "claim_for_account",
+            manifest_args!(account2_address),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey2)],
+    );
+    receipt.expect_commit_success();
+    let account2_nfts = test_runner.get_component_balance(account2_address, reward_nft_address);
+    assert!(
+        account2_nfts == dec!("1"),
+        "Expected 1 rewarded NFT in account 2, but found {:?}",
+        account2_nfts
+    );
+}
+
+#[test]
+pub fn raffle_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (_pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    let (_pubkey2, _, account2_address) = test_runner.new_allocated_account();
+    let seed = String::from("raffle secret");
+    let entry_deadline_epoch = test_runner.get_current_epoch().number() + 1;
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("200"))
+        .take_all_from_worktop(dextr_token, "prize_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "create_raffle",
+                manifest_args!(
+                    String::from("Raffle"),
+                    vec!(dec!("100")),
+                    hash(seed.as_bytes()),
+                    entry_deadline_epoch,
+                    entry_deadline_epoch + 2,
+                    lookup.bucket("prize_bucket")
+                ),
+            )
+        })
+        .call_method(
+            component_address,
+            "add_raffle_tickets",
+            manifest_args!(
+                1u64,
+                vec!(
+                    (RaffleTicket::Account(account1_address), 1u64),
+                    (RaffleTicket::Account(account2_address), 3u64)
+                )
+            ),
+        )
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // the raffle can not be drawn before the entry deadline
+    let draw_manifest = |seed: String| {
+        ManifestBuilder::new()
+            .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+            .call_method(
+                component_address,
+                "draw_raffle",
+                manifest_args!(1u64, seed),
+            )
+            .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+            .build()
+    };
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        draw_manifest(seed.clone()),
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_failure();
+
+    test_runner.set_current_epoch(Epoch::of(entry_deadline_epoch + 1));
+    // the raffle can only be drawn after the ticket registration is closed
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        draw_manifest(seed.clone()),
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_failure();
+    // only admins can close the raffle
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(component_address, "close_raffle", manifest_args!(1u64))
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(tx_manifest, vec![]);
+    receipt.expect_commit_failure();
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(component_address, "close_raffle", manifest_args!(1u64))
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "add_raffle_tickets",
+            manifest_args!(1u64, vec!((RaffleTicket::Account(main_account.2), 100u64))),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_failure();
+
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        draw_manifest(String::from("wrong secret")),
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_failure();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        draw_manifest(seed.clone()),
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    let mut total_prizes = Decimal::ZERO;
+    for account_address in [account1_address, account2_address] {
+        // only the winner receives a rewards NFT
+        if test_runner.get_component_balance(account_address, claim_token_address) == Decimal::ZERO {
+            continue;
+        }
+        let claim_token_data = test_runner.get_non_fungible_data::<AccountRewardsData>(
+            claim_token_address,
+            NonFungibleLocalId::string(account_address.to_hex()).unwrap(),
+        );
+        if let Some(raffle_rewards) = claim_token_data.rewards.get("Raffle") {
+            total_prizes += raffle_rewards.get(&dextr_token).cloned().unwrap_or(Decimal::ZERO);
+        }
+    }
+    assert!(
+        total_prizes == dec!("100"),
+        "Expected raffle prizes of 100, but found {:?}",
+        total_prizes
+    );
+    // a raffle that is not drawn before the reveal deadline is cancelled and the prizes are shared by the tickets,
+    // the last ticket receives the rounding remainder
+    let entry_deadline_epoch = test_runner.get_current_epoch().number();
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "prize_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "create_raffle",
+                manifest_args!(
+                    String::from("Raffle 2"),
+                    vec!(dec!("100")),
+                    hash(seed.as_bytes()),
+                    entry_deadline_epoch,
+                    entry_deadline_epoch + 2,
+                    lookup.bucket("prize_bucket")
+                ),
+            )
+        })
+        .call_method(
+            component_address,
+            "add_raffle_tickets",
+            manifest_args!(
+                2u64,
+                vec!(
+                    (RaffleTicket::Account(account1_address), 1u64),
+                    (RaffleTicket::Account(account2_address), 2u64)
+                )
+            ),
+        )
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let cancel_manifest = ManifestBuilder::new()
+        .call_method(component_address, "cancel_raffle", manifest_args!(2u64))
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(cancel_manifest.clone(), vec![]);
+    receipt.expect_commit_failure();
+
+    test_runner.set_current_epoch(Epoch::of(entry_deadline_epoch + 3));
+    let receipt = test_runner.execute_manifest_ignoring_fee(cancel_manifest, vec![]);
+    receipt.expect_commit_success();
+    for (account_address, expected_refund) in [
+        (account1_address, dec!("33.333333333333333333")),
+        (account2_address, dec!("66.666666666666666667")),
+    ] {
+        let claim_token_data = test_runner.get_non_fungible_data::<AccountRewardsData>(
+            claim_token_address,
+            NonFungibleLocalId::string(account_address.to_hex()).unwrap(),
+        );
+        let refund = claim_token_data
+            .rewards
+            .get("Raffle 2")
+            .and_then(|raffle_rewards| raffle_rewards.get(&dextr_token).cloned())
+            .unwrap_or(Decimal::ZERO);
+        assert!(
+            refund == expected_refund,
+            "Expected raffle refund of {:?}, but found {:?}",
+            expected_refund,
+            refund
+        );
+    }
+
+    // a raffle without tickets can only be refunded to the treasurer
+    let entry_deadline_epoch = test_runner.get_current_epoch().number();
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "prize_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "create_raffle",
+                manifest_args!(
+                    String::from("Raffle 3"),
+                    vec!(dec!("100")),
+                    hash(seed.as_bytes()),
+                    entry_deadline_epoch,
+                    entry_deadline_epoch + 2,
+                    lookup.bucket("prize_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    test_runner.set_current_epoch(Epoch::of(entry_deadline_epoch + 3));
+    let cancel_manifest = ManifestBuilder::new()
+        .call_method(component_address, "cancel_raffle", manifest_args!(3u64))
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(cancel_manifest, vec![]);
+    receipt.expect_commit_failure();
+    let main_balance = test_runner.get_component_balance(main_account.2, dextr_token);
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(component_address, "refund_raffle", manifest_args!(3u64))
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let refunded = test_runner.get_component_balance(main_account.2, dextr_token) - main_balance;
+    assert!(
+        refunded == dec!("100"),
+        "Expected a refund of 100 to the treasurer, but found {:?}",
+        refunded
+    );
+}
+
+#[test]
+pub fn claim_faucet_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, _claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    let (pubkey2, _, account2_address) = test_runner.new_allocated_account();
+    set_owner_keys(&mut test_runner, account1_address, pubkey1);
+    set_owner_keys(&mut test_runner, account2_address, pubkey2);
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("15"))
+        .take_all_from_worktop(dextr_token, "faucet_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "create_faucet",
+                manifest_args!(rule!(allow_all), dec!("10"), lookup.bucket("faucet_bucket")),
+            )
+        })
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    let claim_manifest = |account_address: ComponentAddress| {
+        ManifestBuilder::new()
+            .call_method(
+                component_address,
+                "claim_faucet",
+                manifest_args!(1u64, account_address),
+            )
+            .try_deposit_entire_worktop_or_abort(account_address, None)
+            .build()
+    };
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        claim_manifest(account1_address),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10010"),
+        "Expected Account Balance of 10010, but found {:?}",
+        account_balance
+    );
+    // each account can only claim once
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        claim_manifest(account1_address),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_failure();
+    // only the owner of the account can claim for it
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        claim_manifest(account2_address),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_failure();
+    // the faucet has run out of tokens
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        claim_manifest(account2_address),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey2)],
+    );
+    receipt.expect_commit_failure();
+}
+
+#[test]
+pub fn record_fill_accrual_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, _claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (pair_address, order_receipt_address) =
+        setup_mock_pair(&main_account, component_address, &mut test_runner);
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "register_pair",
+            manifest_args!(
+                order_receipt_address,
+                pair_address,
+                String::from("Trading Rewards"),
+                dextr_token,
+                vec!((dec!("0"), dec!("0.01")), (dec!("1000"), dec!("0.02")))
+            ),
+        )
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "fund_accrual",
+                manifest_args!(order_receipt_address, lookup.bucket("dextr_bucket")),
+            )
+        })
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // fills can only be recorded by the registered pair
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            component_address,
+            "record_fill",
+            manifest_args!(
+                NonFungibleGlobalId::new(order_receipt_address, NonFungibleLocalId::integer(1)),
+                dec!("2000")
+            ),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_failure();
+
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(pair_address, "place_order", manifest_args!(dec!("2000")))
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+
+    let account_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            order_receipt_address,
+            vec![NonFungibleLocalId::integer(1)],
+        )
+        .pop_from_auth_zone("order_receipt")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "claim_rewards",
+                manifest_args!(account_proofs, vec!(lookup.proof("order_receipt"))),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10040"),
+        "Expected Account Balance of 10040, but found {:?}",
+        account_balance
+    );
+
+    // fills of an unregistered pair still go through, without accruing any rewards
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "unregister_pair",
+            manifest_args!(order_receipt_address),
+        )
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(pair_address, "place_order", manifest_args!(dec!("2000")))
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            component_address,
+            "get_accrual_config",
+            manifest_args!(order_receipt_address),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(tx_manifest, vec![]);
+    let accrual_config: Option<AccrualConfig> = receipt.expect_commit_success().output(0);
+    assert!(accrual_config.is_none(), "Expected no accrual config after unregistering the pair");
+}
+
+#[test]
+pub fn strict_claims_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    let (pubkey2, _, account2_address) = test_runner.new_allocated_account();
+    let account1_nft_id = NonFungibleLocalId::string(account1_address.to_hex()).unwrap();
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("100"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .call_method(component_address, "set_strict_claims", manifest_args!(true))
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // move the account1 NFT to account2
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_non_fungibles_from_account(
+            account1_address,
+            claim_token_address,
+            vec![account1_nft_id.clone()],
+        )
+        .try_deposit_entire_worktop_or_abort(account2_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+
+    // claiming with the NFT but without the signature of account1 - should fail
+    let order_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account2_address,
+            claim_token_address,
+            vec![account1_nft_id.clone()],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "claim_rewards",
+                manifest_args!(vec!(lookup.proof("account_nft")), order_proofs.clone()),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account2_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest.clone(),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey2)],
+    );
+    receipt.expect_commit_failure();
+
+    // an allocated account is only owned by the signature of account1 once its owner keys are set - should fail
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest.clone(),
+        vec![
+            NonFungibleGlobalId::from_public_key(&pubkey2),
+            NonFungibleGlobalId::from_public_key(&pubkey1),
+        ],
+    );
+    receipt.expect_commit_failure();
+    set_owner_keys(&mut test_runner, account1_address, pubkey1);
+
+    // claiming with the NFT and the signature of account1 - should succeed
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![
+            NonFungibleGlobalId::from_public_key(&pubkey2),
+            NonFungibleGlobalId::from_public_key(&pubkey1),
+        ],
+    );
+    receipt.expect_commit_success();
+    let account_balance = test_runner.get_component_balance(account2_address, XRD);
+    assert!(
+        account_balance == dec!("10100"),
+        "Expected Account Balance of 10100, but found {:?}",
+        account_balance
+    );
+}
+
+#[test]
+pub fn claim_history_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    let account1_nft_id = NonFungibleLocalId::string(account1_address.to_hex()).unwrap();
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("100"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // no claim history before the first claim
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            component_address,
+            "get_account_data",
+            manifest_args!(account1_nft_id.clone()),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(tx_manifest, vec![]);
+    let account_data: AccountDataV2 = receipt.expect_commit_success().output(0);
+    assert!(account_data.total_claimed.is_empty(), "Expected no claimed rewards");
+    assert!(account_data.last_claim_epoch.is_none(), "Expected no last claim epoch");
+
+    test_runner.set_current_epoch(Epoch::of(10));
+    let order_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![account1_nft_id.clone()],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "claim_rewards",
+                manifest_args!(vec!(lookup.proof("account_nft")), order_proofs),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+
+    // the claim history is kept by the component and survives a super_admin upgrade of the records
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "upgrade_account_data",
+            manifest_args!(vec!(account1_nft_id.clone())),
+        )
+        .drop_all_proofs()
+        .call_method(
+            component_address,
+            "get_account_data",
+            manifest_args!(account1_nft_id.clone()),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let account_data: AccountDataV2 = receipt.expect_commit_success().output(3);
+    assert!(
+        account_data.total_claimed.get(&XRD) == Some(&dec!("100")),
+        "Expected 100 XRD claimed, but found {:?}",
+        account_data.total_claimed
+    );
+    assert!(
+        account_data.last_claim_epoch == Some(10),
+        "Expected last claim epoch 10, but found {:?}",
+        account_data.last_claim_epoch
+    );
+}
+
+#[test]
+pub fn campaign_rewards_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (partner_pubkey, _, partner_address) = test_runner.new_allocated_account();
+    let (_pubkey1, _, account1_address) = test_runner.new_allocated_account();
+
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "create_campaign",
+            manifest_args!(String::from("Partner"), dextr_token, dec!("100")),
+        )
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(partner_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let campaign_badge_address = test_runner
+        .get_component_resources(partner_address)
+        .keys()
+        .find(|resource_address| **resource_address != XRD)
+        .expect("Partner did not receive a campaign manager badge")
+        .clone();
+
+    let add_campaign_rewards_manifest = |amount: Decimal| {
+        ManifestBuilder::new()
+            .withdraw_from_account(partner_address, dextr_token, amount)
+            .take_all_from_worktop(dextr_token, "dextr_bucket")
+            .create_proof_from_account_of_non_fungibles(
+                partner_address,
+                campaign_badge_address,
+                vec![NonFungibleLocalId::integer(1)],
+            )
+            .pop_from_auth_zone("campaign_badge")
+            .with_name_lookup(|builder, lookup| {
+                builder.call_method(
+                    component_address,
+                    "add_campaign_account_rewards",
+                    manifest_args!(
+                        lookup.proof("campaign_badge"),
+                        vec!((account1_address, amount)),
+                        lookup.bucket("dextr_bucket")
+                    ),
+                )
+            })
+            .try_deposit_entire_worktop_or_abort(partner_address, None)
+            .build()
+    };
+
+    // the partner adds rewards within the campaign budget
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        add_campaign_rewards_manifest(dec!("60")),
+        vec![NonFungibleGlobalId::from_public_key(&partner_pubkey)],
+    );
+    receipt.expect_commit_success();
+    check_account_reward_amount(
+        &account1_address,
+        String::from("Campaign: Partner"),
+        &dextr_token,
+        dec!("60"),
+        &claim_token_address,
+        &mut test_runner,
+    );
+
+    // adding more than the remaining budget - should fail
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        add_campaign_rewards_manifest(dec!("50")),
+        vec![NonFungibleGlobalId::from_public_key(&partner_pubkey)],
+    );
+    receipt.expect_commit_failure();
+
+    // admins can not add or remove rewards under the campaign reward name - should fail
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("50"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Campaign: Partner"),
+                    dextr_token,
+                    vec!((account1_address, dec!("50"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_failure();
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "remove_account_rewards",
+            manifest_args!(
+                String::from("Campaign: Partner"),
+                dextr_token,
+                vec!((account1_address, dec!("60")))
+            ),
+        )
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_failure();
+
+    // a revoked badge can no longer manage the campaign - should fail
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "revoke_campaign_manager_badge",
+            manifest_args!(NonFungibleLocalId::integer(1)),
+        )
+        .drop_all_proofs()
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        add_campaign_rewards_manifest(dec!("10")),
+        vec![NonFungibleGlobalId::from_public_key(&partner_pubkey)],
+    );
+    receipt.expect_commit_failure();
+    check_account_reward_amount(
+        &account1_address,
+        String::from("Campaign: Partner"),
+        &dextr_token,
+        dec!("60"),
+        &claim_token_address,
+        &mut test_runner,
+    );
+}
+
+#[test]
+pub fn fee_subsidy_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token =
+        test_runner.create_fungible_resource(dec!("1000"), DIVISIBILITY_MAXIMUM, main_account.2);
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    // the claimer has no XRD to pay for the claim transaction
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(account1_address, XRD, dec!("10000"))
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .withdraw_from_account(main_account.2.clone(), XRD, dec!("100"))
+        .take_all_from_worktop(XRD, "xrd_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder
+                .call_method(
+                    component_address,
+                    "add_account_rewards",
+                    manifest_args!(
+                        String::from("Liquidity Rewards"),
+                        dextr_token,
+                        vec!((account1_address, dec!("100"))),
+                        lookup.bucket("dextr_bucket")
+                    ),
+                )
+                .call_method(
+                    component_address,
+                    "fund_fee_subsidy",
+                    manifest_args!(lookup.bucket("xrd_bucket")),
+                )
+        })
+        .call_method(
+            component_address,
+            "set_fee_subsidy_limits",
+            manifest_args!(dec!("10"), dec!("10")),
+        )
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    let claim_manifest = |fee_payer: Option<ComponentAddress>| {
+        let order_proofs: Vec<ManifestProof> = vec![];
+        let mut builder = ManifestBuilder::new();
+        if let Some(fee_payer_address) = fee_payer {
+            builder = builder.lock_fee(fee_payer_address, dec!("50"));
+        }
+        builder
+            .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+                vec![NonFungibleLocalId::string(account1_address.to_hex()).unwrap()],
+            )
+            .pop_from_auth_zone("account_nft")
+            .with_name_lookup(|builder, lookup| {
+                builder.call_method(
+                    component_address,
+                    "claim_rewards",
+                    manifest_args!(vec!(lookup.proof("account_nft")), order_proofs),
+                )
+            })
+            .try_deposit_entire_worktop_or_abort(account1_address, None)
+            .build()
+    };
+
+    // a claim that pays out rewards is paid by the fee subsidy, so the claimer does not need XRD
+    let receipt = test_runner.execute_manifest(
+        claim_manifest(None),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let subsidy_balance = test_runner.get_component_balance(component_address, XRD);
+    assert!(
+        subsidy_balance < dec!("100") && subsidy_balance >= dec!("90"),
+        "Expected a fee subsidy balance between 90 and 100, but found {:?}",
+        subsidy_balance
+    );
+    let account_rewards = test_runner.get_component_balance(account1_address, dextr_token);
+    assert!(
+        account_rewards == dec!("100"),
+        "Expected 100 claimed rewards, but found {:?}",
+        account_rewards
+    );
+
+    // a claim without rewards does not use the fee subsidy, so its fee is paid by another account
+    let receipt = test_runner.execute_manifest(
+        claim_manifest(Some(main_account.2)),
+        vec![
+            NonFungibleGlobalId::from_public_key(&pubkey1),
+            NonFungibleGlobalId::from_public_key(&main_account.0),
+        ],
+    );
+    receipt.expect_commit_success();
+    let new_subsidy_balance = test_runner.get_component_balance(component_address, XRD);
+    assert!(
+        new_subsidy_balance == subsidy_balance,
+        "Expected the fee subsidy balance to stay at {:?}, but found {:?}",
+        subsidy_balance,
+        new_subsidy_balance
+    );
+}
+
+#[test]
+pub fn claim_rewards_paged_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, _claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (pair_address, order_receipt_address) =
+        setup_mock_pair(&main_account, component_address, &mut test_runner);
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "register_pair",
+            manifest_args!(
+                order_receipt_address,
+                pair_address,
+                String::from("Trading Rewards"),
+                dextr_token,
+                vec!((dec!("0"), dec!("0.01")))
+            ),
+        )
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "fund_accrual",
+                manifest_args!(order_receipt_address, lookup.bucket("dextr_bucket")),
+            )
+        })
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // three orders with 10 XRD rewards each
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(pair_address, "place_order", manifest_args!(dec!("1000")))
+        .call_method(pair_address, "place_order", manifest_args!(dec!("1000")))
+        .call_method(pair_address, "place_order", manifest_args!(dec!("1000")))
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+
+    let claim_page_manifest = |order_ids: Vec<u64>, cursor: Option<ClaimCursor>| {
+        let account_proofs: Vec<ManifestProof> = vec![];
+        ManifestBuilder::new()
+            .create_proof_from_account_of_non_fungibles(
+                account1_address,
+                order_receipt_address,
+                order_ids
+                    .into_iter()
+                    .map(|order_id| NonFungibleLocalId::integer(order_id))
+                    .collect::<Vec<NonFungibleLocalId>>(),
+            )
+            .pop_from_auth_zone("order_receipts")
+            .with_name_lookup(|builder, lookup| {
+                builder.call_method(
+                    component_address,
+                    "claim_rewards_paged",
+                    manifest_args!(
+                        account_proofs,
+                        vec!(lookup.proof("order_receipts")),
+                        2u32,
+                        cursor
+                    ),
+                )
+            })
+            .try_deposit_entire_worktop_or_abort(account1_address, None)
+            .build()
+    };
+
+    // a page with more orders than max orders - should fail
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        claim_page_manifest(vec![1, 2, 3], None),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_failure();
+
+    // a full first page returns a cursor to its last order
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        claim_page_manifest(vec![1, 2], None),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    let (_, cursor): (ScryptoValue, Option<ClaimCursor>) =
+        receipt.expect_commit_success().output(2);
+    assert!(
+        cursor
+            == Some(ClaimCursor {
+                order_receipt_address,
+                order_id: NonFungibleLocalId::integer(2),
+            }),
+        "Expected a cursor to order 2, but found {:?}",
+        cursor
+    );
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10020"),
+        "Expected Account Balance of 10020, but found {:?}",
+        account_balance
+    );
+
+    // a page with orders before the cursor - should fail
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        claim_page_manifest(vec![1], cursor.clone()),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_failure();
+
+    // the last page is not full and returns no cursor
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        claim_page_manifest(vec![3], cursor),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    let (_, cursor): (ScryptoValue, Option<ClaimCursor>) =
+        receipt.expect_commit_success().output(2);
+    assert!(cursor.is_none(), "Expected no cursor after the last page, but found {:?}", cursor);
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10030"),
+        "Expected Account Balance of 10030, but found {:?}",
+        account_balance
+    );
+}
+
+#[test]
+pub fn migrate_accounts_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (successor_address, _successor_dapp_def_address, _successor_nft_address) =
+        setup_component(&main_account, dextr_token, dextr_admin_token, &mut test_runner);
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("100"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    let account_nft_id = NonFungibleLocalId::string(account1_address.to_hex()).unwrap();
+    let order_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![account_nft_id.clone()],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "claim_rewards",
+                manifest_args!(vec!(lookup.proof("account_nft")), order_proofs),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+
+    // NFT rewards that are not claimed yet are migrated with the account
+    let reward_nft_address = test_runner.create_non_fungible_resource(main_account.2);
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_non_fungibles_from_account(
+            main_account.2.clone(),
+            reward_nft_address,
+            vec![NonFungibleLocalId::integer(1)],
+        )
+        .take_all_from_worktop(reward_nft_address, "nft_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_nft_rewards",
+                manifest_args!(
+                    String::from("Event Tickets"),
+                    vec!((account1_address, vec!(NonFungibleLocalId::integer(1)))),
+                    lookup.bucket("nft_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // the successor only accepts migrations from the old component
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .set_main_role(
+            successor_address,
+            "predecessor",
+            rule!(require(global_caller(component_address))),
+        )
+        .call_method(
+            component_address,
+            "migrate_to",
+            manifest_args!(successor_address),
+        )
+        .call_method(
+            component_address,
+            "migrate_accounts",
+            manifest_args!(vec!(account1_address)),
+        )
+        .drop_all_proofs()
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // the successor adds rewards to the existing account NFT
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("50"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                successor_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("50"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let account_nft_count = test_runner.get_component_balance(account1_address, claim_token_address);
+    assert!(
+        account_nft_count == dec!("1"),
+        "Expected 1 account NFT, but found {:?}",
+        account_nft_count
+    );
+
+    let order_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![account_nft_id.clone()],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                successor_address,
+                "claim_rewards",
+                manifest_args!(vec!(lookup.proof("account_nft")), order_proofs),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10150"),
+        "Expected Account Balance of 10150, but found {:?}",
+        account_balance
+    );
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            successor_address,
+            "get_account_data",
+            manifest_args!(account_nft_id),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(tx_manifest, vec![]);
+    let account_data: AccountDataV2 = receipt.expect_commit_success().output(0);
+    assert!(
+        account_data.total_claimed.get(&XRD) == Some(&dec!("150")),
+        "Expected 150 XRD claimed, but found {:?}",
+        account_data.total_claimed
+    );
+    let account_nfts = test_runner.get_component_balance(account1_address, reward_nft_address);
+    assert!(
+        account_nfts == dec!("1"),
+        "Expected 1 rewarded NFT in account, but found {:?}",
+        account_nfts
+    );
+}
+
+#[test]
+pub fn claim_tokens_migration_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (successor_address, successor_dapp_def_address, _successor_nft_address) =
+        setup_component(&main_account, dextr_token, dextr_admin_token, &mut test_runner);
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "set_claim_fee",
+            manifest_args!(dextr_token, 100u16, dec!("0")),
+        )
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("100"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // the claim fee is taken when converting, so the claim tokens are backed 1:1
+    let account_nft_id = NonFungibleLocalId::string(account1_address.to_hex()).unwrap();
+    let order_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![account_nft_id.clone()],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "convert_to_claim_tokens",
+                manifest_args!(vec!(lookup.proof("account_nft")), order_proofs),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    let result = receipt.expect_commit_success();
+    let reward_claim_token = result.new_resource_addresses()[0];
+    let claim_token_balance = test_runner.get_component_balance(account1_address, reward_claim_token);
+    assert!(
+        claim_token_balance == dec!("99"),
+        "Expected 99 claim tokens, but found {:?}",
+        claim_token_balance
+    );
+
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .set_main_role(
+            successor_address,
+            "predecessor",
+            rule!(require(global_caller(component_address))),
+        )
+        .call_method(
+            component_address,
+            "migrate_to",
+            manifest_args!(successor_address),
+        )
+        .drop_all_proofs()
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // the successor mints the claim tokens of the old component when converting rewards
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("50"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                successor_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("50"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let order_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![account_nft_id],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                successor_address,
+                "convert_to_claim_tokens",
+                manifest_args!(vec!(lookup.proof("account_nft")), order_proofs),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let claim_token_balance = test_runner.get_component_balance(account1_address, reward_claim_token);
+    assert!(
+        claim_token_balance == dec!("149"),
+        "Expected 149 claim tokens, but found {:?}",
+        claim_token_balance
+    );
+
+    // the claim tokens of the old component are redeemed by the successor
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(account1_address, reward_claim_token, dec!("149"))
+        .take_all_from_worktop(reward_claim_token, "claim_tokens")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                successor_address,
+                "redeem",
+                manifest_args!(vec!(lookup.bucket("claim_tokens"))),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10149"),
+        "Expected Account Balance of 10149, but found {:?}",
+        account_balance
+    );
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            successor_address,
+            "get_claim_token_liability",
+            manifest_args!(dextr_token),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(tx_manifest, vec![]);
+    let liability: Decimal = receipt.expect_commit_success().output(0);
+    assert!(
+        liability == dec!("0"),
+        "Expected no claim token liability, but found {:?}",
+        liability
+    );
+
+    // the successor updates the dapp definitions of the migrated claim tokens
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            successor_address,
+            "set_dapp_definitions",
+            manifest_args!(vec!(successor_dapp_def_address)),
+        )
+        .drop_all_proofs()
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    assert_eq!(
+        test_runner.get_metadata(reward_claim_token.into(), "dapp_definitions"),
+        Some(MetadataValue::GlobalAddressArray(vec!(successor_dapp_def_address.into())))
+    );
+}
+
+#[test]
+pub fn migrate_allocations_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, _claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (successor_address, _successor_dapp_def_address, _successor_nft_address) =
+        setup_component(&main_account, dextr_token, dextr_admin_token, &mut test_runner);
+    let (pair_address, order_receipt_address) =
+        setup_mock_pair(&main_account, component_address, &mut test_runner);
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    let (pubkey2, _, account2_address) = test_runner.new_allocated_account();
+    set_owner_keys(&mut test_runner, account1_address, pubkey1);
+    set_owner_keys(&mut test_runner, account2_address, pubkey2);
+
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "register_pair",
+            manifest_args!(
+                order_receipt_address,
+                pair_address,
+                String::from("Trading Rewards"),
+                dextr_token,
+                vec!((dec!("0"), dec!("0.01")))
+            ),
+        )
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "fund_accrual",
+                manifest_args!(order_receipt_address, lookup.bucket("dextr_bucket")),
+            )
+        })
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("50"))
+        .take_all_from_worktop(dextr_token, "faucet_bucket")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "create_faucet",
+                manifest_args!(rule!(allow_all), dec!("10"), lookup.bucket("faucet_bucket")),
+            )
+        })
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // two orders accrue 20 and 5 rewards
+    for volume in [dec!("2000"), dec!("500")] {
+        let tx_manifest = ManifestBuilder::new()
+            .call_method(pair_address, "place_order", manifest_args!(volume))
+            .try_deposit_entire_worktop_or_abort(account1_address, None)
+            .build();
+        let receipt = test_runner.execute_manifest_ignoring_fee(
+            tx_manifest,
+            vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+        );
+        receipt.expect_commit_success();
+    }
+    let order_receipt_string = AddressBech32Encoder::new(&NetworkDefinition::simulator())
+        .encode(order_receipt_address.as_ref())
+        .unwrap();
+    let order1_id = format!("{}#1#", order_receipt_string);
+    let order2_id = format!("{}#2#", order_receipt_string);
+    let account_ids: Vec<NonFungibleLocalId> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "freeze_rewards",
+            manifest_args!(account_ids, vec!(order1_id.clone())),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    let faucet_manifest = |component_address: ComponentAddress, account_address: ComponentAddress| {
+        ManifestBuilder::new()
+            .call_method(
+                component_address,
+                "claim_faucet",
+                manifest_args!(1u64, account_address),
+            )
+            .try_deposit_entire_worktop_or_abort(account_address, None)
+            .build()
+    };
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        faucet_manifest(component_address, account1_address),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .set_main_role(
+            successor_address,
+            "predecessor",
+            rule!(require(global_caller(component_address))),
+        )
+        .call_method(
+            component_address,
+            "migrate_to",
+            manifest_args!(successor_address),
+        )
+        .call_method(
+            component_address,
+            "migrate_orders",
+            manifest_args!(vec!(order1_id.clone(), order2_id.clone())),
+        )
+        .call_method(
+            component_address,
+            "migrate_accounts",
+            manifest_args!(vec!(account1_address)),
+        )
+        .drop_all_proofs()
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // the faucet and the accrual config keep their remaining tokens in the successor
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(successor_address, "get_faucet", manifest_args!(1u64))
+        .call_method(
+            successor_address,
+            "get_accrual_config",
+            manifest_args!(order_receipt_address),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(tx_manifest, vec![]);
+    let result = receipt.expect_commit_success();
+    let faucet: Option<FaucetData> = result.output(0);
+    assert!(
+        faucet.as_ref().map(|faucet| faucet.remaining) == Some(dec!("40")),
+        "Expected 40 remaining in the migrated faucet, but found {:?}",
+        faucet
+    );
+    let accrual_config: Option<AccrualConfig> = result.output(1);
+    assert!(
+        accrual_config.as_ref().map(|config| config.remaining_budget) == Some(dec!("75")),
+        "Expected a remaining budget of 75 in the migrated accrual config, but found {:?}",
+        accrual_config
+    );
+
+    // faucet claims are migrated with the account
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        faucet_manifest(successor_address, account1_address),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_failure();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        faucet_manifest(successor_address, account2_address),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey2)],
+    );
+    receipt.expect_commit_success();
+    let account_balance = test_runner.get_component_balance(account2_address, XRD);
+    assert!(
+        account_balance == dec!("10010"),
+        "Expected Account Balance of 10010, but found {:?}",
+        account_balance
+    );
+
+    // the frozen order stays frozen in the successor
+    let account_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            order_receipt_address,
+            vec![NonFungibleLocalId::integer(1), NonFungibleLocalId::integer(2)],
+        )
+        .pop_from_auth_zone("order_receipts")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                successor_address,
+                "claim_rewards",
+                manifest_args!(account_proofs, vec!(lookup.proof("order_receipts"))),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10015"),
+        "Expected Account Balance of 10015, but found {:?}",
+        account_balance
+    );
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            successor_address,
+            "get_orders_rewards",
+            manifest_args!(vec!(order1_id.clone())),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(tx_manifest, vec![]);
+    let orders_rewards: Vec<OrderRewardsData> = receipt.expect_commit_success().output(0);
+    assert!(
+        orders_rewards.len() == 1
+            && orders_rewards[0].rewards.get("Trading Rewards").and_then(|rewards| rewards.get(&XRD))
+                == Some(&dec!("20")),
+        "Expected 20 frozen rewards on the first order, but found {:?}",
+        orders_rewards
+    );
+}
+
+#[test]
+pub fn freeze_rewards_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("100"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    let account_nft_id = NonFungibleLocalId::string(account1_address.to_hex()).unwrap();
+    let order_ids: Vec<String> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "freeze_rewards",
+            manifest_args!(vec!(account_nft_id.clone()), order_ids.clone()),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let frozen_events: Vec<RewardsFrozenEvent> =
+        get_events(&test_runner, receipt.expect_commit_success(), "RewardsFrozenEvent");
+    assert!(
+        frozen_events.len() == 1 && frozen_events[0].account_ids == vec![account_nft_id.clone()],
+        "Expected a frozen event for the account NFT, but found {:?}",
+        frozen_events
+    );
+
+    let claim_manifest = || {
+        let order_proofs: Vec<ManifestProof> = vec![];
+        ManifestBuilder::new()
+            .create_proof_from_account_of_non_fungibles(
+                account1_address,
+                claim_token_address,
+                vec![account_nft_id.clone()],
+            )
+            .pop_from_auth_zone("account_nft")
+            .with_name_lookup(|builder, lookup| {
+                builder.call_method(
+                    component_address,
+                    "claim_rewards",
+                    manifest_args!(vec!(lookup.proof("account_nft")), order_proofs),
+                )
+            })
+            .try_deposit_entire_worktop_or_abort(account1_address, None)
+            .build()
+    };
+    // frozen rewards stay on the NFT when claiming
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        claim_manifest(),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10000"),
+        "Expected Account Balance of 10000, but found {:?}",
+        account_balance
+    );
+
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "unfreeze_rewards",
+            manifest_args!(vec!(account_nft_id.clone()), order_ids),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let unfrozen_events: Vec<RewardsUnfrozenEvent> =
+        get_events(&test_runner, receipt.expect_commit_success(), "RewardsUnfrozenEvent");
+    assert!(
+        unfrozen_events.len() == 1 && unfrozen_events[0].account_ids == vec![account_nft_id.clone()],
+        "Expected an unfrozen event for the account NFT, but found {:?}",
+        unfrozen_events
+    );
+
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        claim_manifest(),
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10100"),
+        "Expected Account Balance of 10100, but found {:?}",
+        account_balance
+    );
+}
+
+#[test]
+pub fn soulbound_nft_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let package_address = test_runner.compile_and_publish(this_package!());
+    let tx_manifest = ManifestBuilder::new()
+        .call_function(
+            package_address,
+            "DexterClaimComponent",
+            "new",
+            manifest_args!(
+                String::from("DeXter Claim Component"),
+                String::from("DeXter Liquidity and Trading Rewards Claim Component."),
+                String::from("https://dexteronradix.com/logo_icon.svg"),
+                dextr_admin_token,
+                dextr_admin_token,
+                dextr_admin_token,
+                true
+            ),
+        )
+        .try_deposit_entire_worktop_or_abort(main_account.2, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let result = receipt.expect_commit_success();
+    let component_address = result.new_component_addresses()[0];
+    let claim_token_address = result.new_resource_addresses()[1];
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    let (_pubkey2, _, account2_address) = test_runner.new_allocated_account();
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("100"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // the account can not transfer a soulbound NFT
+    let account_nft_id = NonFungibleLocalId::string(account1_address.to_hex()).unwrap();
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_non_fungibles_from_account(
+            account1_address,
+            claim_token_address,
+            vec![account_nft_id.clone()],
+        )
+        .try_deposit_entire_worktop_or_abort(account2_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_failure();
+
+    // a super_admin can recall the NFT and return it to its account
+    let nft_vault_id = test_runner.get_component_vaults(account1_address, claim_token_address)[0];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .recall_non_fungibles(
+            InternalAddress::new_or_panic(nft_vault_id.into()),
+            vec![account_nft_id.clone()],
+        )
+        .take_all_from_worktop(claim_token_address, "account_nfts")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "return_account_nfts",
+                manifest_args!(account1_address, lookup.bucket("account_nfts")),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let account_nfts = test_runner.get_component_balance(account1_address, claim_token_address);
+    assert!(
+        account_nfts == dec!("1"),
+        "Expected the account NFT to be returned to the account, but found {:?}",
+        account_nfts
+    );
+    let main_account_nfts = test_runner.get_component_balance(main_account.2, claim_token_address);
+    assert!(
+        main_account_nfts == dec!("0"),
+        "Expected no account NFT in the main account, but found {:?}",
+        main_account_nfts
+    );
+}
+
+#[test]
+pub fn claim_for_account_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    // claim_for_account accepts the signature of a virtual account as owner proof
+    let (pubkey1, _, account1_address) = test_runner.new_virtual_account();
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("100"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // the rewards NFT can be moved away, the account owner can still claim the rewards of the account
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(account1_address, claim_token_address, dec!("1"))
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            component_address,
+            "claim_for_account",
+            manifest_args!(account1_address),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest.clone(),
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_failure();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10100"),
+        "Expected Account Balance of 10100, but found {:?}",
+        account_balance
+    );
+}
+
+#[test]
+pub fn pending_rewards_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    // claim_for_account accepts the signature of a virtual account as owner proof
+    let (pubkey1, _, account1_address) = test_runner.new_virtual_account();
+    let set_nft_preference = |test_runner: &mut TestRunner<NoExtension, InMemorySubstateDatabase>,
+                              preference: ResourcePreference| {
+        let tx_manifest = ManifestBuilder::new()
+            .call_method(
+                account1_address,
+                "set_resource_preference",
+                manifest_args!(claim_token_address, preference),
+            )
+            .build();
+        let receipt = test_runner.execute_manifest_ignoring_fee(
+            tx_manifest,
+            vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+        );
+        receipt.expect_commit_success();
+    };
+    let add_rewards_manifest = |amount: Decimal| {
+        ManifestBuilder::new()
+            .withdraw_from_account(main_account.2.clone(), dextr_token, amount)
+            .take_all_from_worktop(dextr_token, "dextr_bucket")
+            .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+            .with_name_lookup(|builder, lookup| {
+                builder.call_method(
+                    component_address,
+                    "add_account_rewards",
+                    manifest_args!(
+                        String::from("Liquidity Rewards"),
+                        dextr_token.clone(),
+                        vec!((account1_address, amount)),
+                        lookup.bucket("dextr_bucket")
+                    ),
+                )
+            })
+            .drop_all_proofs()
+            .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+            .build()
+    };
+
+    // rewards for an account that refuses the rewards NFT are kept as pending rewards
+    set_nft_preference(&mut test_runner, ResourcePreference::Disallowed);
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        add_rewards_manifest(dec!("100")),
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let pending_events: Vec<PendingRewardsEvent> =
+        get_events(&test_runner, receipt.expect_commit_success(), "PendingRewardsEvent");
+    assert!(
+        pending_events.len() == 1
+            && pending_events[0].account_address == account1_address
+            && pending_events[0].amount == dec!("100"),
+        "Expected a pending rewards event of 100 for the account, but found {:?}",
+        pending_events
+    );
+    let account_nfts = test_runner.get_component_balance(account1_address, claim_token_address);
+    assert!(
+        account_nfts == dec!("0"),
+        "Expected no account rewards NFT, but found {:?}",
+        account_nfts
+    );
+
+    // the account owner can claim pending rewards without the rewards NFT
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            component_address,
+            "claim_for_account",
+            manifest_args!(account1_address),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest.clone(),
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_failure();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10100"),
+        "Expected Account Balance of 10100, but found {:?}",
+        account_balance
+    );
+
+    // pending rewards are moved to the rewards NFT once the account accepts it
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        add_rewards_manifest(dec!("50")),
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    set_nft_preference(&mut test_runner, ResourcePreference::Allowed);
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            component_address,
+            "deliver_pending_rewards",
+            manifest_args!(account1_address),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(tx_manifest, vec![]);
+    receipt.expect_commit_success();
+    let claim_token_data = test_runner.get_non_fungible_data::<AccountRewardsData>(
+        claim_token_address,
+        NonFungibleLocalId::string(account1_address.to_hex()).unwrap(),
+    );
+    let nft_rewards = claim_token_data
+        .rewards
+        .get("Liquidity Rewards")
+        .and_then(|name_rewards| name_rewards.get(&dextr_token).cloned())
+        .unwrap_or(Decimal::ZERO);
+    assert!(
+        nft_rewards == dec!("50"),
+        "Expected 50 rewards on the account NFT, but found {:?}",
+        nft_rewards
+    );
+}
+
+#[test]
+pub fn rewards_report_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, _claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (_pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    let (_pubkey2, _, account2_address) = test_runner.new_allocated_account();
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("100"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let (_bucket, report): (Bucket, RewardsReport) = receipt.expect_commit_success().output(3);
+    assert!(
+        report.entries.len() == 1 && report.entries[0].status == RewardEntryStatus::Applied,
+        "Expected the added rewards to be applied, but found {:?}",
+        report.entries
+    );
+
+    // removals are limited to the existing rewards, entries without rewards are skipped
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "remove_account_rewards",
+            manifest_args!(
+                String::from("Liquidity Rewards"),
+                dextr_token.clone(),
+                vec!((account1_address, dec!("150")), (account2_address, dec!("10")))
+            ),
+        )
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let (_bucket, report): (Bucket, RewardsReport) = receipt.expect_commit_success().output(1);
+    assert!(
+        report.total_amount == dec!("100"),
+        "Expected 100 removed rewards, but found {:?}",
+        report.total_amount
+    );
+    assert!(
+        report.entries.len() == 2,
+        "Expected 2 report entries, but found {:?}",
+        report.entries
+    );
+    assert!(
+        report.entries[0].entry == RewardEntry::Account(account1_address)
+            && report.entries[0].status == RewardEntryStatus::Clamped
+            && report.entries[0].applied_amount == dec!("100"),
+        "Expected the removal for account 1 to be clamped to 100, but found {:?}",
+        report.entries[0]
+    );
+    assert!(
+        report.entries[1].entry == RewardEntry::Account(account2_address)
+            && report.entries[1].status == RewardEntryStatus::SkippedNotFound
+            && report.entries[1].applied_amount == dec!("0"),
+        "Expected the removal for account 2 to be skipped, but found {:?}",
+        report.entries[1]
+    );
+    let account_balance = test_runner.get_component_balance(main_account.2.clone(), XRD);
+    assert!(
+        account_balance == dec!("10000"),
+        "Expected Account Balance of 10000, but found {:?}",
+        account_balance
+    );
+}
+
+#[test]
+pub fn airdrop_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (_pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    let (pubkey2, _, account2_address) = test_runner.new_allocated_account();
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            account2_address,
+            "set_resource_preference",
+            manifest_args!(dextr_token, ResourcePreference::Disallowed),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey2)],
+    );
+    receipt.expect_commit_success();
+
+    // accounts that refuse the airdrop receive the tokens as claimable rewards
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "airdrop_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "airdrop",
+                manifest_args!(
+                    lookup.bucket("airdrop_bucket"),
+                    vec!((account1_address, dec!("10")), (account2_address, dec!("20")))
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    let account1_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account1_balance == dec!("10010"),
+        "Expected Account 1 Balance of 10010, but found {:?}",
+        account1_balance
+    );
+    let account2_balance = test_runner.get_component_balance(account2_address, XRD);
+    assert!(
+        account2_balance == dec!("10000"),
+        "Expected Account 2 Balance of 10000, but found {:?}",
+        account2_balance
+    );
+    let main_account_balance = test_runner.get_component_balance(main_account.2.clone(), XRD);
+    assert!(
+        main_account_balance == dec!("9970"),
+        "Expected Main Account Balance of 9970, but found {:?}",
+        main_account_balance
+    );
+    let claim_token_data = test_runner.get_non_fungible_data::<AccountRewardsData>(
+        claim_token_address,
+        NonFungibleLocalId::string(account2_address.to_hex()).unwrap(),
+    );
+    let airdrop_rewards = claim_token_data
+        .rewards
+        .get("Airdrop")
+        .and_then(|name_rewards| name_rewards.get(&dextr_token).cloned())
+        .unwrap_or(Decimal::ZERO);
+    assert!(
+        airdrop_rewards == dec!("20"),
+        "Expected 20 airdrop rewards on the account NFT, but found {:?}",
+        airdrop_rewards
+    );
+}
+
+#[test]
+pub fn claim_fee_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "set_claim_fee",
+            manifest_args!(dextr_token, 250u16, dec!("1")),
+        )
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("100"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    let order_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![NonFungibleLocalId::string(account1_address.to_hex()).unwrap()],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "claim_rewards",
+                manifest_args!(vec!(lookup.proof("account_nft")), order_proofs),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    let claimed_events: Vec<RewardsClaimedEvent> =
+        get_events(&test_runner, receipt.expect_commit_success(), "RewardsClaimedEvent");
+    assert!(
+        claimed_events.len() == 1 && claimed_events[0].claimed_amounts.len() == 1,
+        "Expected one claimed token amount, but found {:?}",
+        claimed_events
+    );
+    let claimed_amount = &claimed_events[0].claimed_amounts[0];
+    assert!(
+        claimed_amount.gross_amount == dec!("100")
+            && claimed_amount.fee_amount == dec!("2.5")
+            && claimed_amount.net_amount == dec!("97.5"),
+        "Expected a fee of 2.5 on 100 claimed, but found {:?}",
+        claimed_amount
+    );
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10097.5"),
+        "Expected Account Balance of 10097.5, but found {:?}",
+        account_balance
+    );
+
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(component_address, "withdraw_fees", manifest_args!(dextr_token))
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let main_account_balance = test_runner.get_component_balance(main_account.2.clone(), XRD);
+    assert!(
+        main_account_balance == dec!("9902.5"),
+        "Expected Main Account Balance of 9902.5, but found {:?}",
+        main_account_balance
+    );
+
+    // the minimum fee is charged once per claim, also when NFTs with payout splits are paid out separately
+    let (pubkey2, _, account2_address) = test_runner.new_allocated_account();
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("40"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "set_claim_fee",
+            manifest_args!(dextr_token, 0u16, dec!("5")),
+        )
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("20")), (account2_address, dec!("20"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let account1_nft_id = NonFungibleLocalId::string(account1_address.to_hex()).unwrap();
+    let account2_nft_id = NonFungibleLocalId::string(account2_address.to_hex()).unwrap();
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_non_fungibles_from_account(
+            account2_address,
+            claim_token_address,
+            vec![account2_nft_id.clone()],
+        )
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey2)],
+    );
+    receipt.expect_commit_success();
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![account1_nft_id.clone()],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "set_payout_splits",
+                manifest_args!(lookup.proof("account_nft"), vec!((account1_address, dec!("100")))),
+            )
+        })
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+
+    let order_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![account1_nft_id, account2_nft_id],
+        )
+        .pop_from_auth_zone("account_nfts")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "claim_rewards",
+                manifest_args!(vec!(lookup.proof("account_nfts")), order_proofs),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    let claimed_events: Vec<RewardsClaimedEvent> =
+        get_events(&test_runner, receipt.expect_commit_success(), "RewardsClaimedEvent");
+    let fee_total: Decimal = claimed_events
+        .iter()
+        .flat_map(|claimed_event| claimed_event.claimed_amounts.iter())
+        .map(|claimed_amount| claimed_amount.fee_amount)
+        .fold(Decimal::ZERO, |fee_total, fee_amount| fee_total + fee_amount);
+    assert!(
+        claimed_events.len() == 2 && fee_total == dec!("5"),
+        "Expected a minimum fee of 5 over two payouts, but found {:?}",
+        claimed_events
+    );
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10132.5"),
+        "Expected Account Balance of 10132.5, but found {:?}",
+        account_balance
+    );
+}
+
+#[test]
+pub fn claim_and_stake_validator_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    let validator_address = test_runner.new_validator_with_pub_key(pubkey1, account1_address);
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "set_restrict_validators",
+            manifest_args!(true),
+        )
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("100"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    // claimed rewards can not be staked to a validator that is not approved
+    let order_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![NonFungibleLocalId::string(account1_address.to_hex()).unwrap()],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "claim_and_stake",
+                manifest_args!(vec!(lookup.proof("account_nft")), order_proofs, validator_address),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_failure();
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10000"),
+        "Expected Account Balance of 10000, but found {:?}",
+        account_balance
+    );
+    let claim_token_data = test_runner.get_non_fungible_data::<AccountRewardsData>(
+        claim_token_address,
+        NonFungibleLocalId::string(account1_address.to_hex()).unwrap(),
+    );
+    let account_rewards = claim_token_data
+        .rewards
+        .get("Liquidity Rewards")
+        .and_then(|name_rewards| name_rewards.get(&dextr_token).cloned())
+        .unwrap_or(Decimal::ZERO);
+    assert!(
+        account_rewards == dec!("100"),
+        "Expected the rewards to stay on the account NFT, but found {:?}",
+        account_rewards
+    );
+
+    // claimed XRD is staked to an approved validator that accepts delegated stake
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            VALIDATOR_OWNER_BADGE,
+            vec![NonFungibleLocalId::bytes(validator_address.as_node_id().0).unwrap()],
+        )
+        .call_method(
+            validator_address,
+            "update_accept_delegated_stake",
+            manifest_args!(true),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "approve_validators",
+            manifest_args!(vec!(validator_address)),
+        )
+        .drop_all_proofs()
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let order_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![NonFungibleLocalId::string(account1_address.to_hex()).unwrap()],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "claim_and_stake",
+                manifest_args!(vec!(lookup.proof("account_nft")), order_proofs, validator_address),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let stake_unit_address = test_runner.get_validator_info(validator_address).stake_unit_resource;
+    let stake_unit_balance = test_runner.get_component_balance(account1_address, stake_unit_address);
+    assert!(
+        stake_unit_balance == dec!("100"),
+        "Expected 100 stake units, but found {:?}",
+        stake_unit_balance
+    );
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10000"),
+        "Expected Account Balance of 10000, but found {:?}",
+        account_balance
+    );
+}
+
+#[test]
+pub fn claim_and_forward_whitelist_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_token = XRD;
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, claim_token_address) = setup_component(
+        &main_account,
+        dextr_token,
+        dextr_admin_token,
+        &mut test_runner,
+    );
+    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    let forward_target = (component_address, String::from("fund_fee_subsidy"));
+
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), dextr_token, dec!("100"))
+        .take_all_from_worktop(dextr_token, "dextr_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    dextr_token.clone(),
+                    vec!((account1_address, dec!("100"))),
+                    lookup.bucket("dextr_bucket")
+                ),
+            )
+        })
+        // a target that is removed from the whitelist can no longer be used
+        .call_method(
+            component_address,
+            "add_forward_targets",
+            manifest_args!(vec!(forward_target.clone())),
+        )
+        .call_method(
+            component_address,
+            "remove_forward_targets",
+            manifest_args!(vec!(forward_target.clone())),
+        )
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+
+    let order_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![NonFungibleLocalId::string(account1_address.to_hex()).unwrap()],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "claim_and_forward",
+                manifest_args!(
+                    vec!(lookup.proof("account_nft")),
+                    order_proofs,
+                    forward_target.0,
+                    forward_target.1.clone(),
+                    dextr_token
+                ),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_failure();
+    let claim_token_data = test_runner.get_non_fungible_data::<AccountRewardsData>(
+        claim_token_address,
+        NonFungibleLocalId::string(account1_address.to_hex()).unwrap(),
+    );
+    let account_rewards = claim_token_data
+        .rewards
+        .get("Liquidity Rewards")
+        .and_then(|name_rewards| name_rewards.get(&dextr_token).cloned())
+        .unwrap_or(Decimal::ZERO);
+    assert!(
+        account_rewards == dec!("100"),
+        "Expected the rewards to stay on the account NFT, but found {:?}",
+        account_rewards
+    );
+
+    // a whitelisted target receives the forwarded rewards and the other claimed rewards are returned
+    let (pair_address, _order_receipt_address) =
+        setup_mock_pair(&main_account, component_address, &mut test_runner);
+    let other_token =
+        test_runner.create_fungible_resource(dec!(1000), DIVISIBILITY_MAXIMUM, main_account.2);
+    let pair_target = (pair_address, String::from("deposit"));
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2.clone(), other_token, dec!("50"))
+        .take_all_from_worktop(other_token, "other_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_account_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    other_token,
+                    vec!((account1_address, dec!("50"))),
+                    lookup.bucket("other_bucket")
+                ),
+            )
+        })
+        .call_method(
+            component_address,
+            "add_forward_targets",
+            manifest_args!(vec!(pair_target.clone())),
+        )
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2.clone(), None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    let order_proofs: Vec<ManifestProof> = vec![];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_non_fungibles(
+            account1_address,
+            claim_token_address,
+            vec![NonFungibleLocalId::string(account1_address.to_hex()).unwrap()],
+        )
+        .pop_from_auth_zone("account_nft")
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "claim_and_forward",
+                manifest_args!(
+                    vec!(lookup.proof("account_nft")),
+                    order_proofs,
+                    pair_target.0,
+                    pair_target.1.clone(),
+                    dextr_token
+                ),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account1_address, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey1)],
+    );
+    receipt.expect_commit_success();
+    let pair_balance = test_runner.get_component_balance(pair_address, XRD);
+    assert!(
+        pair_balance == dec!("100"),
+        "Expected 100 XRD forwarded to the pair, but found {:?}",
+        pair_balance
+    );
+    let account_balance = test_runner.get_component_balance(account1_address, XRD);
+    assert!(
+        account_balance == dec!("10000"),
+        "Expected Account Balance of 10000, but found {:?}",
+        account_balance
+    );
+    let other_token_balance = test_runner.get_component_balance(account1_address, other_token);
+    assert!(
+        other_token_balance == dec!("50"),
+        "Expected 50 of the other reward token returned to the account, but found {:?}",
+        other_token_balance
+    );
+}
+
+#[test]
+pub fn change_dapp_def_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (_component_address, dapp_def_address, _claim_token_address) =
+        setup_component(&main_account, XRD, dextr_admin_token, &mut test_runner);
+
+    // change metadata with authorisation - should succeed
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .set_metadata(dapp_def_address, "name", "DeXter Claim Component 2")
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    // println!("Receipt: {:?}", receipt);
+    let _result = receipt.expect_commit_success();
+
+    // change metadata without authorisation = should fail
+    let tx_manifest = ManifestBuilder::new()
+        .set_metadata(dapp_def_address, "name", "DeXter Claim Component 2")
+        .try_deposit_entire_worktop_or_abort(main_account.2, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    // println!("Receipt: {:?}", receipt);
+    let _result = receipt.expect_commit_failure();
+}
+
+#[test]
+pub fn owner_metadata_methods_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(1), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, dapp_def_address, claim_token_address) =
+        setup_component(&main_account, XRD, dextr_admin_token, &mut test_runner);
+
+    // update the name without the owner badge - should fail
+    let tx_manifest = ManifestBuilder::new()
+        .call_method(
+            component_address,
+            "update_name",
+            manifest_args!(String::from("DeXter Rewards")),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_failure();
+
+    // update the name and description with the owner badge - should update the component, the NFT and the dapp definition
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "update_name",
+            manifest_args!(String::from("DeXter Rewards")),
+        )
+        .call_method(
+            component_address,
+            "update_description",
+            manifest_args!(String::from("DeXter Rewards Claim Component.")),
+        )
+        .drop_all_proofs()
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    assert_eq!(
+        test_runner.get_metadata(component_address.into(), "name"),
+        Some(MetadataValue::String(String::from("DeXter Rewards")))
+    );
+    assert_eq!(
+        test_runner.get_metadata(claim_token_address.into(), "name"),
+        Some(MetadataValue::String(String::from("DeXter Rewards NFT")))
+    );
+    assert_eq!(
+        test_runner.get_metadata(dapp_def_address.into(), "name"),
+        Some(MetadataValue::String(String::from("DeXter Rewards")))
+    );
+    assert_eq!(
+        test_runner.get_metadata(dapp_def_address.into(), "description"),
+        Some(MetadataValue::String(String::from(
+            "DeXter Rewards Claim Component."
+        )))
+    );
+    assert_eq!(
+        test_runner.get_metadata(claim_token_address.into(), "description"),
+        Some(MetadataValue::String(String::from(
+            "DeXter Rewards Claim Component."
+        )))
+    );
+
+    // switch to a new dapp definition that the component is allowed to update
+    let tx_manifest = ManifestBuilder::new()
+        .new_account_advanced(
+            OwnerRole::Updatable(rule!(
+                require(global_caller(component_address)) || require(dextr_admin_token)
+            )),
+            None,
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let new_dapp_def_address = receipt.expect_commit_success().new_component_addresses()[0];
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .call_method(
+            component_address,
+            "set_dapp_definitions",
+            manifest_args!(vec!(new_dapp_def_address)),
+        )
+        .call_method(
+            component_address,
+            "update_name",
+            manifest_args!(String::from("DeXter Claims")),
+        )
+        .drop_all_proofs()
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    receipt.expect_commit_success();
+    assert_eq!(
+        test_runner.get_metadata(component_address.into(), "dapp_definition"),
+        Some(MetadataValue::GlobalAddress(new_dapp_def_address.into()))
+    );
+    assert_eq!(
+        test_runner.get_metadata(new_dapp_def_address.into(), "name"),
+        Some(MetadataValue::String(String::from("DeXter Claims")))
+    );
+    // the old dapp definition is no longer updated
+    assert_eq!(
+        test_runner.get_metadata(dapp_def_address.into(), "name"),
+        Some(MetadataValue::String(String::from("DeXter Rewards")))
+    );
+}
+
+#[test]
+pub fn change_admin_role_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let (_pubkey1, _, account1_address) = test_runner.new_allocated_account();
+    let dextr_admin_token =
+        test_runner.create_fungible_resource(dec!(2), DIVISIBILITY_NONE, main_account.2);
+    let (component_address, _dapp_def_address, _claim_token_address) =
+        setup_component(&main_account, XRD, dextr_admin_token, &mut test_runner);
+
+    // change admin role without authorisation - should fail
+    let tx_manifest = ManifestBuilder::new()
+        .set_main_role(
+            component_address.clone(),
+            "admin",
+            rule!(require_amount(2, dextr_admin_token.clone())),
+        )
+        .try_deposit_entire_worktop_or_abort(main_account.2, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    // println!("Receipt: {:?}", receipt);
+    let _result = receipt.expect_commit_failure();
+
+    // change admin role with authorisation - should succeed
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .set_main_role(
+            component_address.clone(),
+            "admin",
+            rule!(require_amount(2, dextr_admin_token.clone())),
+        )
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    // println!("Receipt: {:?}", receipt);
+    let _result = receipt.expect_commit_success();
+
+    // try to run admins-only protected method - should fail
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2, XRD, dec!("2000"))
+        .take_all_from_worktop(XRD, "xrd_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 1)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    XRD,
+                    vec!((account1_address, dec!("123.34"))),
+                    String::from(""),
+                    lookup.bucket("xrd_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2, None)
+        .build();
+
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    // println!("Receipt: {:?}", receipt);
+    let _result = receipt.expect_commit_failure();
+
+    // try to run admins-only protected method again with proper authorisation - should succeed
+    let tx_manifest = ManifestBuilder::new()
+        .withdraw_from_account(main_account.2, XRD, dec!("2000"))
+        .take_all_from_worktop(XRD, "xrd_bucket")
+        .create_proof_from_account_of_amount(main_account.2.clone(), dextr_admin_token, 2)
+        .with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                component_address,
+                "add_rewards",
+                manifest_args!(
+                    String::from("Liquidity Rewards"),
+                    XRD,
+                    vec!((account1_address, dec!("123.34"))),
+                    String::from(""),
+                    lookup.bucket("xrd_bucket")
+                ),
+            )
+        })
+        .drop_all_proofs()
+        .try_deposit_entire_worktop_or_abort(main_account.2, None)
+        .build();
+
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    // println!("Receipt: {:?}", receipt);
+    let _result = receipt.expect_commit_success();
+}
+
+#[test]
+pub fn new_with_badges_test() {
+    let mut test_runner = TestRunnerBuilder::new().without_trace().build();
+    let main_account = test_runner.new_allocated_account();
+    let package_address = test_runner.compile_and_publish(this_package!());
+
+    let tx_manifest = ManifestBuilder::new()
+        .call_function(
+            package_address,
+            "DexterClaimComponent",
+            "new_with_badges",
+            manifest_args!(
+                String::from("DeXter Claim Component"),
+                String::from("DeXter Liquidity and Trading Rewards Claim Component."),
+                String::from("https://dexteronradix.com/logo_icon.svg"),
+                false
+            ),
+        )
+        .try_deposit_entire_worktop_or_abort(main_account.2, None)
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let result = receipt.expect_commit_success();
+    let component_address = result.new_component_addresses()[0];
+    let owner_badge = result.new_resource_addresses()[0];
+    let super_admin_badge = result.new_resource_addresses()[1];
+    let admin_badge = result.new_resource_addresses()[2];
+    for badge in [owner_badge, super_admin_badge, admin_badge] {
+        let badge_balance = test_runner.get_component_balance(main_account.2, badge);
+        assert!(
+            badge_balance == dec!(1),
+            "Expected badge balance of 1, but found {:?}",
+            badge_balance
+        );
+    }
+
+    // deactivate without super admin badge - should fail
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), admin_badge, 1)
+        .call_method(component_address, "deactivate", manifest_args!())
+        .drop_all_proofs()
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let _result = receipt.expect_commit_failure();
+
+    // deactivate with super admin badge - should succeed
+    let tx_manifest = ManifestBuilder::new()
+        .create_proof_from_account_of_amount(main_account.2.clone(), super_admin_badge, 1)
+        .call_method(component_address, "deactivate", manifest_args!())
+        .drop_all_proofs()
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let _result = receipt.expect_commit_success();
+}
+
+fn check_account_reward_amount(
     account_address: &ComponentAddress,
     reward_name: String,
     token_address: &ResourceAddress,
@@ -1350,69 +4998,69 @@ fn check_account_reward_amount(
 //     );
 // }
 
-fn build_accounts_test_str(
-    test_runner: &mut TestRunner<NoExtension, InMemorySubstateDatabase>,
-) -> (String, Vec<(String, Secp256k1PublicKey, Decimal)>) {
-    println!("Starting to create test str...");
-    let mut account_addresses: Vec<(String, Secp256k1PublicKey, Decimal)> = vec![];
-    let xrd_string = XRD.to_hex();
-    let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
-    // test_runner.load_account_from_faucet(account1_address);
-    let account1_address_string = account1_address.to_hex();
-    println!(
-        "New account created. Pub key: {:?}, Address: {:?}, Address hex: {:?}",
-        pubkey1, account1_address, account1_address_string
-    );
-    // let account_address_str = Runtime::bech32_encode_address(account_address);
-    account_addresses.push((account1_address_string.clone(), pubkey1, dec!("10357.79")));
-    let (pubkey2, _, account2_address) = test_runner.new_allocated_account();
-    // test_runner.load_account_from_faucet(account2_address);
-    // println!(
-    //     "New account created. Pub key: {:?}, Address: {:?}",
-    //     pubkey2, account2_address
-    // );
-    // let account_address_str = Runtime::bech32_encode_address(account_address);
-    let account2_address_string = account2_address.to_hex();
-    account_addresses.push((account2_address_string.clone(), pubkey2, dec!("10801.67")));
-    let rewards_string = format!(
-        r##"
-    {{
-        'reward_names': [
-            [1, 'Liquidity Rewards'],
-            [2, 'Trading Rewards']
-        ],
-        'tokens': [
-            [1, '{xrd_string}']
-        ],
-        'accounts': [
-            [
-                '{account1_address_string}', [
-                    [1, [[1, '123.34']]],
-                    [2, [[1, '234.45']]]
-                ]
-            ],
-            [
-                '{account2_address_string}', [
-                    [1, [[1, '345.67']]],
-                    [2, [[1, '456']]]
-                ]
-            ]
-        ],
-        'orders': []
-    }}
-    "##
-    );
-    let trimmed_rewards_string = rewards_string
-        .replace("\n", "")
-        .replace("\r", "")
-        .replace(" ", "")
-        .clone();
-    // println!(
-    //     "Output string trimmed: {:?}",
-    //     trimmed_rewards_string.clone()
-    // );
-    (trimmed_rewards_string, account_addresses)
-}
+// fn build_accounts_test_str(
+//     test_runner: &mut TestRunner<NoExtension, InMemorySubstateDatabase>,
+// ) -> (String, Vec<(String, Secp256k1PublicKey, Decimal)>) {
+//     println!("Starting to create test str...");
+//     let mut account_addresses: Vec<(String, Secp256k1PublicKey, Decimal)> = vec![];
+//     let xrd_string = XRD.to_hex();
+//     let (pubkey1, _, account1_address) = test_runner.new_allocated_account();
+//     // test_runner.load_account_from_faucet(account1_address);
+//     let account1_address_string = account1_address.to_hex();
+//     println!(
+//         "New account created. Pub key: {:?}, Address: {:?}, Address hex: {:?}",
+//         pubkey1, account1_address, account1_address_string
+//     );
+//     // let account_address_str = Runtime::bech32_encode_address(account_address);
+//     account_addresses.push((account1_address_string.clone(), pubkey1, dec!("10357.79")));
+//     let (pubkey2, _, account2_address) = test_runner.new_allocated_account();
+//     // test_runner.load_account_from_faucet(account2_address);
+//     // println!(
+//     //     "New account created. Pub key: {:?}, Address: {:?}",
+//     //     pubkey2, account2_address
+//     // );
+//     // let account_address_str = Runtime::bech32_encode_address(account_address);
+//     let account2_address_string = account2_address.to_hex();
+//     account_addresses.push((account2_address_string.clone(), pubkey2, dec!("10801.67")));
+//     let rewards_string = format!(
+//         r##"
+//     {{
+//         'reward_names': [
+//             [1, 'Liquidity Rewards'],
+//             [2, 'Trading Rewards']
+//         ],
+//         'tokens': [
+//             [1, '{xrd_string}']
+//         ],
+//         'accounts': [
+//             [
+//                 '{account1_address_string}', [
+//                     [1, [[1, '123.34']]],
+//                     [2, [[1, '234.45']]]
+//                 ]
+//             ],
+//             [
+//                 '{account2_address_string}', [
+//                     [1, [[1, '345.67']]],
+//                     [2, [[1, '456']]]
+//                 ]
+//             ]
+//         ],
+//         'orders': []
+//     }}
+//     "##
+//     );
+//     let trimmed_rewards_string = rewards_string
+//         .replace("\n", "")
+//         .replace("\r", "")
+//         .replace(" ", "")
+//         .clone();
+//     // println!(
+//     //     "Output string trimmed: {:?}",
+//     //     trimmed_rewards_string.clone()
+//     // );
+//     (trimmed_rewards_string, account_addresses)
+// }
 
 // fn build_remove_accounts_test_str(account_addresses: Vec<String>) -> String {
 //     println!("Starting to create remove accounts test str...");
@@ -1459,6 +5107,41 @@ fn build_accounts_test_str(
 //     trimmed_rewards_string
 // }
 
+// Accounts prove their owner to the claim component with the keys in their owner_keys metadata.
+fn set_owner_keys(
+    test_runner: &mut TestRunner<NoExtension, InMemorySubstateDatabase>,
+    account_address: ComponentAddress,
+    pubkey: Secp256k1PublicKey,
+) {
+    let tx_manifest = ManifestBuilder::new()
+        .set_metadata(
+            account_address,
+            "owner_keys",
+            MetadataValue::PublicKeyHashArray(vec![PublicKeyHash::Secp256k1(
+                Secp256k1PublicKeyHash::new_from_public_key(&pubkey),
+            )]),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&pubkey)],
+    );
+    receipt.expect_commit_success();
+}
+
+fn get_events<T: ScryptoDecode>(
+    test_runner: &TestRunner<NoExtension, InMemorySubstateDatabase>,
+    result: &CommitResult,
+    event_name: &str,
+) -> Vec<T> {
+    result
+        .application_events
+        .iter()
+        .filter(|(event_type_id, _event_data)| test_runner.event_name(event_type_id) == event_name)
+        .map(|(_event_type_id, event_data)| scrypto_decode::<T>(event_data).unwrap())
+        .collect()
+}
+
 fn build_orders_test_str(
     test_runner: &mut TestRunner<NoExtension, InMemorySubstateDatabase>,
 ) -> String {
@@ -1514,7 +5197,7 @@ fn build_orders_test_str(
 
 fn setup_component(
     main_account: &(Secp256k1PublicKey, Secp256k1PrivateKey, ComponentAddress),
-    dextr_token: ResourceAddress,
+    _dextr_token: ResourceAddress,
     dextr_admin_token: ResourceAddress,
     test_runner: &mut TestRunner<NoExtension, InMemorySubstateDatabase>,
 ) -> (ComponentAddress, ComponentAddress, ResourceAddress) {
@@ -1525,7 +5208,15 @@ fn setup_component(
             package_address,
             "DexterClaimComponent",
             "new",
-            manifest_args!(dextr_token, dextr_admin_token),
+            manifest_args!(
+                String::from("DeXter Claim Component"),
+                String::from("DeXter Liquidity and Trading Rewards Claim Component."),
+                String::from("https://dexteronradix.com/logo_icon.svg"),
+                dextr_admin_token,
+                dextr_admin_token,
+                dextr_admin_token,
+                false
+            ),
         )
         .try_deposit_entire_worktop_or_abort(main_account.2, None)
         .build();
@@ -1543,10 +5234,44 @@ fn setup_component(
     // );
     let claim_component_address = result.new_component_addresses()[0];
     let dapp_def_address = result.new_component_addresses()[1];
-    let claim_token_address = result.new_resource_addresses()[0];
+    // the first new resource is the nft authority badge
+    let claim_token_address = result.new_resource_addresses()[1];
     (
         claim_component_address,
         dapp_def_address,
         claim_token_address,
     )
 }
+
+// The mock pair blueprint is only part of the package when it is compiled with the test feature.
+fn setup_mock_pair(
+    main_account: &(Secp256k1PublicKey, Secp256k1PrivateKey, ComponentAddress),
+    claim_component_address: ComponentAddress,
+    test_runner: &mut TestRunner<NoExtension, InMemorySubstateDatabase>,
+) -> (ComponentAddress, ResourceAddress) {
+    let (code, definition) = Compile::compile_with_env_vars(
+        this_package!(),
+        btreemap! {
+            "RUSTFLAGS".to_owned() => "".to_owned(),
+            "CARGO_ENCODED_RUSTFLAGS".to_owned() => "--cfg\u{1f}feature=\"test\"".to_owned(),
+        },
+    );
+    let package_address = test_runner.publish_package(code, definition, BTreeMap::new(), OwnerRole::None);
+
+    let tx_manifest = ManifestBuilder::new()
+        .call_function(
+            package_address,
+            "MockPair",
+            "new",
+            manifest_args!(claim_component_address),
+        )
+        .build();
+    let receipt = test_runner.execute_manifest_ignoring_fee(
+        tx_manifest,
+        vec![NonFungibleGlobalId::from_public_key(&main_account.0)],
+    );
+    let result = receipt.expect_commit_success();
+    let pair_address = result.new_component_addresses()[0];
+    let order_receipt_address = result.new_resource_addresses()[0];
+    (pair_address, order_receipt_address)
+}